@@ -72,6 +72,7 @@
             src = ./.;
             filter = path: type:
               (pkgs.lib.hasSuffix "\.css" path) ||
+              (pkgs.lib.hasSuffix "\.ftl" path) ||
               (config.rust-project.crane-lib.filterCargoSources path type)
             ;
           };
@@ -1,8 +1,15 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use gtk::glib;
+
 use crate::config::Config;
+use crate::i18n::tr;
 use crate::messaging;
 use crate::messaging::AppMessage;
+use crate::messaging::VideoMessage;
 use crate::prelude::*;
-use crate::widgets::calendar::CalendarWidget;
+use crate::widgets::calendar::{CalendarWidget, key_controller};
 use crate::widgets::grafana::GrafanaWidget;
 use crate::widgets::screensaver::ScreensaverWidget;
 use crate::widgets::video::Video;
@@ -10,17 +17,27 @@ use crate::widgets::video::Video;
 pub struct App {
     #[allow(unused)]
     window: gtk::ApplicationWindow,
+    paned: gtk::Paned,
+    stack: gtk::Stack,
     calendar: CalendarWidget,
     video: Video,
     screensaver: ScreensaverWidget,
     grafana: GrafanaWidget,
+
+    stream_timeout: Option<glib::SourceId>,
+    // Paned position, stack page and whether the video grid was shown before a stream
+    stream_layout: Rc<RefCell<Option<(i32, Option<glib::GString>, bool)>>>,
 }
 
 impl App {
     pub fn new(app: &gtk::Application, config: &Config) -> Self {
         let calendar = CalendarWidget::new(config);
         let video = Video::new(config);
-        let screensaver = ScreensaverWidget::new(config, calendar.upcoming());
+        let screensaver = ScreensaverWidget::new(
+            config,
+            calendar.upcoming(),
+            calendar.screensaver_countdown(),
+        );
         let grafana = GrafanaWidget::new(config);
 
         let paned = gtk::Paned::new(gtk::Orientation::Horizontal);
@@ -35,8 +52,9 @@ impl App {
         let stack = gtk::Stack::new();
         stack.set_transition_type(gtk::StackTransitionType::SlideLeftRight);
         stack.set_transition_duration(300);
-        stack.add_titled(&paned, Some("calendar"), "Kalender");
-        stack.add_titled(grafana.widget(), Some("grafana"), "Grafana");
+        stack.add_titled(&paned, Some("calendar"), &tr("page-calendar"));
+        stack.add_titled(calendar.year().widget(), Some("year"), &tr("page-year"));
+        stack.add_titled(grafana.widget(), Some("grafana"), &tr("page-grafana"));
 
         let stack_switcher = gtk::StackSwitcher::new();
         stack_switcher.set_hexpand(true);
@@ -64,14 +82,19 @@ impl App {
         window.set_default_height(600);
         window.set_child(Some(&overlay));
         window.add_controller(controller);
+        window.add_controller(key_controller(&stack));
         window.present();
 
         Self {
             window,
+            paned,
+            stack,
             calendar,
             video,
             screensaver,
             grafana,
+            stream_timeout: None,
+            stream_layout: Rc::default(),
         }
     }
 
@@ -80,9 +103,47 @@ impl App {
 
         match message {
             AppMessage::Calendar(message) => self.calendar.update(message),
-            AppMessage::Video(message) => self.video.update(message),
+            AppMessage::Video(message) => {
+                if let VideoMessage::StreamShown { duration, grid } = &message {
+                    self.show_video(*duration, *grid);
+                }
+
+                self.video.update(message);
+            }
             AppMessage::Screensaver(message) => self.screensaver.update(message),
             AppMessage::Grafana(message) => self.grafana.update(message),
         }
     }
+
+    /// Give the whole window to the video for the given duration, returning to the grid
+    /// afterwards if it was shown before
+    fn show_video(&mut self, duration: std::time::Duration, grid: bool) {
+        remove_source(self.stream_timeout.take());
+
+        // Keep the layout from before the first of several overlapping streams
+        self.stream_layout
+            .borrow_mut()
+            .get_or_insert_with(|| (self.paned.position(), self.stack.visible_child_name(), grid));
+
+        self.stack.set_visible_child_name("calendar");
+        self.paned.set_position(0);
+
+        let paned = self.paned.clone();
+        let stack = self.stack.clone();
+        let layout = self.stream_layout.clone();
+
+        self.stream_timeout = Some(glib::timeout_add_local_once(duration, move || {
+            if let Some((position, page, grid)) = layout.borrow_mut().take() {
+                paned.set_position(position);
+
+                if let Some(page) = page {
+                    stack.set_visible_child_name(&page);
+                }
+
+                if grid {
+                    messaging::send_message(VideoMessage::ShowGrid);
+                }
+            }
+        }));
+    }
 }
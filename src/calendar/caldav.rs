@@ -1,14 +1,14 @@
 use std::time::Duration;
 
 use base64::prelude::*;
-use chrono::NaiveDate;
+use chrono::{NaiveDate, NaiveDateTime};
 use ureq::config::Config;
 use ureq::http::Request;
 use ureq::{Agent, http};
 use url::Url;
 
 use super::event_builder::{EventBuilder, EventBuilderError};
-use super::{Calendar, Event};
+use super::{Calendar, Color, Event, extract, free_busy};
 use crate::config;
 
 use super::map::CalendarMap;
@@ -161,6 +161,70 @@ impl Client {
         }
     }
 
+    /// Send a REPORT with the given body to the given url and return the parsed result XML.
+    ///
+    /// # Errors
+    /// Returns an error if the request or the XML parsing fails.
+    pub fn report(&self, url: &str, body: &str, depth: &str) -> Result<xmltree::Element, Error> {
+        let mut content = self.report_body(url, body, depth)?;
+
+        Ok(xmltree::Element::parse(content.as_reader())?)
+    }
+
+    fn report_body(&self, url: &str, body: &str, depth: &str) -> Result<ureq::Body, Error> {
+        let auth = self.get_auth_header();
+
+        let request = http::Request::builder()
+            .method("REPORT")
+            .uri(url)
+            .header("Authorization", &auth)
+            .header("Depth", depth)
+            .header("Content-Type", "application/xml")
+            .body(body)
+            .map_err(|e| Error {
+                kind: ErrorKind::Parsing,
+                message: e.to_string(),
+            })?;
+
+        self.request(request)
+    }
+
+    /// Get the busy periods of a calendar shared with free/busy access only,
+    /// using a `free-busy-query` REPORT answered with a `VFREEBUSY` component.
+    ///
+    /// # Errors
+    /// Returns an error if the request fails.
+    pub fn get_free_busy(
+        &self,
+        url: &Url,
+        time_range: &TimeRangeFilter,
+    ) -> Result<Vec<(NaiveDateTime, NaiveDateTime)>, Error> {
+        let data = self
+            .report_body(url.as_str(), &request_free_busy(time_range), "1")?
+            .read_to_string()?;
+
+        Ok(free_busy::parse_free_busy(&data))
+    }
+
+    /// Get the color of a single calendar, e.g. one shared with free/busy access only.
+    ///
+    /// # Errors
+    /// Returns an error if the request or the XML parsing fails.
+    pub fn get_calendar_color(&self, url: &Url) -> Result<Option<Color>, Error> {
+        let root = self.propfind_get(url, CALENDARS_REQUEST, &[], "0")?.1;
+
+        Ok(root
+            .children
+            .iter()
+            .filter_map(|c| c.as_element())
+            .filter_map(extract::calendar_color)
+            .find_map(|color| color.parse().ok()))
+    }
+
+    pub const fn base_url(&self) -> &Url {
+        &self.base_url
+    }
+
     /// Get the `CalDAV` principal URL for the given credentials from the caldav server.
     ///
     /// # Errors
@@ -475,6 +539,18 @@ pub fn request_event(filter: &TimeRangeFilter) -> String {
     )
 }
 
+pub fn request_free_busy(filter: &TimeRangeFilter) -> String {
+    format!(
+        r#"
+<c:free-busy-query xmlns:c="urn:ietf:params:xml:ns:caldav">
+    <c:time-range start="{}" end="{}" />
+</c:free-busy-query>
+    "#,
+        (filter.start()).format("%Y%m%dT000000Z"),
+        (filter.end()).format("%Y%m%dT000000Z")
+    )
+}
+
 pub fn request_todos(filter: &str) -> String {
     format!(
         r#"<c:calendar-query xmlns:d="DAV:" xmlns:c="urn:ietf:params:xml:ns:caldav">
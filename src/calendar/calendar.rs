@@ -5,12 +5,60 @@ use crate::calendar::Color;
 
 use super::extract;
 
+/// Color of calendars the server has no color for
+pub const DEFAULT_COLOR: Color = Rgb::new(222, 184, 135);
+
 #[derive(Clone, Debug)]
 pub struct Calendar {
     pub uid: Uuid,
     pub url_str: String,
     pub name: String,
     pub color: Color,
+    pub kind: CalendarKind,
+    pub shape: IndicatorShape,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CalendarKind {
+    /// Fetched from the `CalDAV` server
+    CalDav,
+    /// Generated public holidays
+    Holidays,
+    /// Imported school vacations
+    SchoolVacations,
+    /// Generated from `CardDAV` contacts
+    Birthdays,
+    /// Busy periods of a calendar shared with free/busy access only
+    FreeBusy,
+}
+
+/// Shape of the calendar indicators in the month grid, to tell calendars apart without colors
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IndicatorShape {
+    #[default]
+    Dot,
+    Square,
+    Ring,
+}
+
+impl IndicatorShape {
+    pub const ALL: [Self; 3] = [Self::Dot, Self::Square, Self::Ring];
+
+    pub const fn css_class(self) -> &'static str {
+        match self {
+            Self::Dot => "dot",
+            Self::Square => "square",
+            Self::Ring => "ring",
+        }
+    }
+}
+
+impl CalendarKind {
+    /// Holidays and vacations are shown as day styling rather than as events
+    pub const fn is_holiday(self) -> bool {
+        matches!(self, Self::Holidays | Self::SchoolVacations)
+    }
 }
 
 impl Calendar {
@@ -29,7 +77,9 @@ impl Calendar {
             name: extract::calendar_name(element)?,
             color: extract::calendar_color(element)
                 .and_then(|color| color.parse().ok())
-                .unwrap_or_else(|| Rgb::new(222, 184, 135)),
+                .unwrap_or(DEFAULT_COLOR),
+            kind: CalendarKind::CalDav,
+            shape: IndicatorShape::default(),
         })
     }
 
@@ -43,6 +93,12 @@ impl Calendar {
     pub fn fg_color(&self) -> Color {
         fg_from_bg_w3c(self.color)
     }
+
+    pub fn css_fg_color(&self) -> String {
+        let color = self.fg_color();
+
+        format!("rgb({}, {}, {})", color.red, color.green, color.blue)
+    }
 }
 
 impl PartialEq for Calendar {
@@ -0,0 +1,404 @@
+use chrono::{Datelike, Days, NaiveDate, NaiveTime};
+use palette::rgb::Rgb;
+use url::Url;
+use uuid::Uuid;
+
+use super::caldav::{self, Credentials, Error};
+use super::{Calendar, CalendarKind, CalendarMap, Event, IndicatorShape, extract};
+use crate::config;
+use crate::i18n::{tr, tr_args};
+
+/// `CardDAV` client reading birthdays and anniversaries from the address books of an account.
+#[derive(Debug, Clone)]
+pub struct Client {
+    inner: caldav::Client,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DateKind {
+    Birthday,
+    Anniversary,
+}
+
+#[derive(Debug)]
+struct ContactDate {
+    name: String,
+    kind: DateKind,
+    month: u32,
+    day: u32,
+    year: Option<i32>,
+}
+
+impl Client {
+    pub fn new(base_url: Url, credentials: Credentials) -> Self {
+        Self {
+            inner: caldav::Client::new(base_url, credentials),
+        }
+    }
+
+    /// Get the addressbook homeset url for the given credentials from the carddav server.
+    ///
+    /// # Errors
+    /// Returns an error if the request or the XML parsing fails.
+    pub fn get_home_set_url(&self, url: &Url) -> Result<Url, Error> {
+        let principal_url = self
+            .inner
+            .get_principal_url(url)
+            .unwrap_or_else(|_| url.clone());
+
+        let homeset_url = self
+            .inner
+            .propfind_get(
+                &principal_url,
+                ADDRESSBOOK_HOMESET_REQUEST,
+                &[
+                    "response",
+                    "propstat",
+                    "prop",
+                    "addressbook-home-set",
+                    "href",
+                ],
+                "0",
+            )?
+            .0;
+
+        Ok(url.join(&homeset_url)?)
+    }
+
+    /// Get the urls of all address books for the given credentials.
+    ///
+    /// # Errors
+    /// Returns an error if the request or the XML parsing fails.
+    pub fn get_addressbooks(&self) -> Result<Vec<Url>, Error> {
+        let base_url = self.inner.base_url();
+        let homeset_url = self
+            .get_home_set_url(base_url)
+            .unwrap_or_else(|_| base_url.clone());
+
+        let root = self
+            .inner
+            .propfind_get(&homeset_url, ADDRESSBOOKS_REQUEST, &[], "1")?
+            .1;
+
+        let addressbooks = root
+            .children
+            .iter()
+            .filter_map(|c| c.as_element())
+            .filter(|element| extract::is_addressbook(element))
+            .filter_map(extract::href)
+            .filter_map(|href| base_url.join(&href).ok())
+            .collect();
+
+        Ok(addressbooks)
+    }
+
+    /// Add a calendar with yearly birthday and anniversary events of all contacts to the map.
+    ///
+    /// # Errors
+    /// Returns an error if a request or the XML parsing fails.
+    pub fn add_to_map(
+        &self,
+        map: &mut CalendarMap,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<(), Error> {
+        let url_str = format!("{}#birthdays", self.inner.base_url());
+
+        let calendar = Calendar {
+            uid: Uuid::new_v5(&Uuid::NAMESPACE_URL, url_str.as_bytes()),
+            url_str,
+            name: tr("calendar-birthdays"),
+            color: Rgb::new(219, 39, 119),
+            kind: CalendarKind::Birthdays,
+            shape: IndicatorShape::default(),
+        };
+
+        // A failing address book must not hide the birthdays of the others
+        for addressbook in self.get_addressbooks()? {
+            if let Err(err) = self.add_addressbook(&addressbook, &calendar, map, start, end) {
+                log::error!("Calendar: failed to fetch birthdays of {addressbook}: {err:?}");
+            }
+        }
+
+        map.add_calendar(calendar);
+
+        Ok(())
+    }
+
+    fn add_addressbook(
+        &self,
+        addressbook: &Url,
+        calendar: &Calendar,
+        map: &mut CalendarMap,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<(), Error> {
+        let root = self
+            .inner
+            .report(addressbook.as_str(), ADDRESSBOOK_QUERY, "1")?;
+
+        for element in root.children.iter().filter_map(|c| c.as_element()) {
+            let (Some(href), Some(data)) = (extract::href(element), extract::address_data(element))
+            else {
+                continue;
+            };
+
+            let url = addressbook.join(&href)?;
+
+            for date in parse_vcard(&data) {
+                for year in start.year()..=end.year() {
+                    if let Some(event) = date.event(&url, year) {
+                        map.add_event(calendar.uid, event);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl From<config::Contacts> for Credentials {
+    fn from(contacts: config::Contacts) -> Self {
+        Self::Basic(contacts.username, contacts.password)
+    }
+}
+
+impl ContactDate {
+    fn event(&self, url: &Url, year: i32) -> Option<Event> {
+        // Leap day birthdays are celebrated on the 28th in other years
+        let date = NaiveDate::from_ymd_opt(year, self.month, self.day)
+            .or_else(|| NaiveDate::from_ymd_opt(year, self.month, self.day - 1))?;
+
+        let years = self.year.map(|born| year - born).filter(|years| *years > 0);
+
+        let summary = match (self.kind, years) {
+            (DateKind::Birthday, Some(age)) => tr_args(
+                "birthday-age",
+                &[("name", self.name.as_str().into()), ("age", age.into())],
+            ),
+            (DateKind::Birthday, None) => {
+                tr_args("birthday", &[("name", self.name.as_str().into())])
+            }
+            (DateKind::Anniversary, Some(years)) => tr_args(
+                "anniversary-years",
+                &[("name", self.name.as_str().into()), ("years", years.into())],
+            ),
+            (DateKind::Anniversary, None) => {
+                tr_args("anniversary", &[("name", self.name.as_str().into())])
+            }
+        };
+
+        let id = format!("{url}#{:?}-{year}", self.kind);
+
+        Some(Event {
+            etag: String::new(),
+            uid: Uuid::new_v5(&Uuid::NAMESPACE_URL, id.as_bytes()),
+            summary,
+            description: None,
+            location: None,
+            start: date.and_time(NaiveTime::MIN),
+            end: (date + Days::new(1)).and_time(NaiveTime::MIN),
+            url: url.clone(),
+            rrule: None,
+        })
+    }
+}
+
+fn parse_vcard(data: &str) -> Vec<ContactDate> {
+    let mut name = None;
+    let mut dates = Vec::new();
+
+    for line in unfold(data).lines() {
+        let Some((property, value)) = line.split_once(':') else {
+            continue;
+        };
+
+        let mut params = property.split(';');
+        // Properties may be grouped, e.g. `item1.BDAY`
+        let property_name = params
+            .next()
+            .unwrap_or_default()
+            .rsplit('.')
+            .next()
+            .unwrap_or_default()
+            .to_ascii_uppercase();
+        // Apple marks birthdays without a year with a placeholder year
+        let omit_year =
+            params.any(|param| param.to_ascii_uppercase().starts_with("X-APPLE-OMIT-YEAR"));
+
+        let kind = match property_name.as_str() {
+            "FN" => {
+                name = Some(unescape(value.trim()));
+                continue;
+            }
+            "BDAY" => DateKind::Birthday,
+            "ANNIVERSARY" | "X-ANNIVERSARY" => DateKind::Anniversary,
+            _ => continue,
+        };
+
+        if let Some((year, month, day)) = parse_vcard_date(value.trim()) {
+            dates.push((kind, year.filter(|_| !omit_year), month, day));
+        }
+    }
+
+    let Some(name) = name.filter(|name| !name.is_empty()) else {
+        return Vec::new();
+    };
+
+    dates
+        .into_iter()
+        .map(|(kind, year, month, day)| ContactDate {
+            name: name.clone(),
+            kind,
+            month,
+            day,
+            year,
+        })
+        .collect()
+}
+
+/// Joins folded lines, continuations start with a space or tab
+fn unfold(data: &str) -> String {
+    data.replace("\r\n", "\n")
+        .replace("\n ", "")
+        .replace("\n\t", "")
+}
+
+/// Resolves the escaped `\,`, `\;`, `\\` and `\n` of text values
+fn unescape(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+
+    while let Some(char) = chars.next() {
+        if char != '\\' {
+            unescaped.push(char);
+            continue;
+        }
+
+        match chars.next() {
+            Some('n' | 'N') => unescaped.push('\n'),
+            Some(escaped) => unescaped.push(escaped),
+            None => unescaped.push('\\'),
+        }
+    }
+
+    unescaped
+}
+
+/// Parse `19450312`, `1945-03-12`, `--0312` and `--03-12`, optionally followed by a time.
+fn parse_vcard_date(value: &str) -> Option<(Option<i32>, u32, u32)> {
+    let date = value.split('T').next()?.replace('-', "");
+
+    let (year, month_day) = if value.starts_with("--") {
+        (None, date.as_str())
+    } else if date.len() >= 8 {
+        (Some(date.get(0..4)?.parse().ok()?), date.get(4..)?)
+    } else {
+        return None;
+    };
+
+    let month = month_day.get(0..2)?.parse().ok()?;
+    let day = month_day.get(2..4)?.parse().ok()?;
+
+    NaiveDate::from_ymd_opt(2000, month, day)?;
+
+    Some((year, month, day))
+}
+
+pub static ADDRESSBOOK_HOMESET_REQUEST: &str = r#"
+<d:propfind xmlns:d="DAV:" xmlns:card="urn:ietf:params:xml:ns:carddav" >
+    <d:self/>
+    <d:prop>
+        <card:addressbook-home-set />
+    </d:prop>
+</d:propfind>
+"#;
+
+pub static ADDRESSBOOKS_REQUEST: &str = r#"
+<d:propfind xmlns:d="DAV:" >
+    <d:prop>
+        <d:displayname />
+        <d:resourcetype />
+    </d:prop>
+</d:propfind>
+"#;
+
+pub static ADDRESSBOOK_QUERY: &str = r#"
+<card:addressbook-query xmlns:d="DAV:" xmlns:card="urn:ietf:params:xml:ns:carddav">
+    <d:prop>
+        <d:getetag />
+        <card:address-data>
+            <card:prop name="FN" />
+            <card:prop name="BDAY" />
+            <card:prop name="ANNIVERSARY" />
+            <card:prop name="X-ANNIVERSARY" />
+        </card:address-data>
+    </d:prop>
+    <card:filter test="anyof">
+        <card:prop-filter name="BDAY" />
+        <card:prop-filter name="ANNIVERSARY" />
+        <card:prop-filter name="X-ANNIVERSARY" />
+    </card:filter>
+</card:addressbook-query>
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dates(data: &str) -> Vec<(String, DateKind, Option<i32>, u32, u32)> {
+        parse_vcard(data)
+            .into_iter()
+            .map(|date| (date.name, date.kind, date.year, date.month, date.day))
+            .collect()
+    }
+
+    #[test]
+    fn vcard() {
+        let data = "BEGIN:VCARD\r\n\
+            VERSION:4.0\r\n\
+            FN:Müller\\, Anna\\; Dr.\r\n\
+            BDAY:19\r\n 850312\r\n\
+            item1.X-ANNIVERSARY:2010-06-\r\n\t01\r\n\
+            END:VCARD\r\n";
+
+        assert_eq!(
+            dates(data),
+            [
+                (
+                    "Müller, Anna; Dr.".to_string(),
+                    DateKind::Birthday,
+                    Some(1985),
+                    3,
+                    12
+                ),
+                (
+                    "Müller, Anna; Dr.".to_string(),
+                    DateKind::Anniversary,
+                    Some(2010),
+                    6,
+                    1
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn vcard_without_year() {
+        let data = "BEGIN:VCARD\nFN:Anna\nBDAY;X-APPLE-OMIT-YEAR=1604:1604-03-12\nBDAY:--0401\nEND:VCARD\n";
+
+        assert_eq!(
+            dates(data),
+            [
+                ("Anna".to_string(), DateKind::Birthday, None, 3, 12),
+                ("Anna".to_string(), DateKind::Birthday, None, 4, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn unescaped_text() {
+        assert_eq!(unescape(r"a\,b\;c\\d\ne\Nf\"), "a,b;c\\d\ne\nf\\");
+    }
+}
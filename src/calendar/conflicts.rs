@@ -0,0 +1,155 @@
+use chrono::NaiveDateTime;
+
+use super::{Calendar, Event};
+
+/// One occurrence of an event
+pub type Occurrence<'a> = (&'a Calendar, &'a NaiveDateTime, &'a Event);
+
+/// Two timed occurrences overlapping each other
+#[derive(Debug, Clone, Copy)]
+pub struct Conflict<'a> {
+    pub first: Occurrence<'a>,
+    pub second: Occurrence<'a>,
+    /// Start of the overlapping time span
+    pub start: NaiveDateTime,
+}
+
+/// Find all pairs of overlapping timed occurrences.
+/// Occurrences must be the starts of the events ordered by their start, like
+/// [`CalendarMap::occurrences_between`](super::CalendarMap::occurrences_between) yields them.
+/// All-day events are ignored.
+pub fn find<'a>(occurrences: impl Iterator<Item = Occurrence<'a>>) -> Vec<Conflict<'a>> {
+    let mut active: Vec<(NaiveDateTime, Occurrence<'a>)> = Vec::new();
+    let mut conflicts = Vec::new();
+
+    for occurrence @ (_, start, event) in occurrences {
+        if event.is_all_day() || event.start == event.end {
+            continue;
+        }
+
+        active.retain(|(end, _)| end > start);
+
+        for (_, other) in &active {
+            if other.2.uid != event.uid {
+                conflicts.push(Conflict {
+                    first: *other,
+                    second: occurrence,
+                    start: *start,
+                });
+            }
+        }
+
+        active.push((*start + (event.end - event.start), occurrence));
+    }
+
+    conflicts
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+    use palette::rgb::Rgb;
+    use rrule::{RRule, Unvalidated};
+    use url::Url;
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::calendar::{CalendarKind, CalendarMap, IndicatorShape};
+
+    const DAILY: Option<&str> = Some("FREQ=DAILY;COUNT=3");
+
+    fn date_time(value: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    fn event(summary: &str, start: &str, end: &str, rrule: Option<&str>) -> Event {
+        Event {
+            etag: String::new(),
+            uid: Uuid::new_v5(&Uuid::NAMESPACE_URL, summary.as_bytes()),
+            summary: summary.to_string(),
+            description: None,
+            location: None,
+            start: date_time(start),
+            end: date_time(end),
+            url: Url::parse("https://example.com/event.ics").unwrap(),
+            rrule: rrule.map(|rrule| rrule.parse::<RRule<Unvalidated>>().unwrap()),
+        }
+    }
+
+    fn conflicts(events: Vec<Event>) -> Vec<(String, String, NaiveDateTime)> {
+        let calendar = Calendar {
+            uid: Uuid::new_v5(&Uuid::NAMESPACE_URL, b"https://example.com/calendar"),
+            url_str: "https://example.com/calendar".to_string(),
+            name: "Calendar".to_string(),
+            color: Rgb::new(0, 0, 0),
+            kind: CalendarKind::CalDav,
+            shape: IndicatorShape::default(),
+        };
+
+        let mut map = CalendarMap::default();
+        let calendar_uid = calendar.uid;
+        map.add_calendar(calendar);
+
+        for event in events {
+            map.add_event(calendar_uid, event);
+        }
+
+        let start = NaiveDate::from_ymd_opt(2026, 3, 1).unwrap();
+        let end = NaiveDate::from_ymd_opt(2026, 3, 31).unwrap();
+
+        find(map.occurrences_between(start, end))
+            .into_iter()
+            .map(|conflict| {
+                (
+                    conflict.first.2.summary.clone(),
+                    conflict.second.2.summary.clone(),
+                    conflict.start,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn daily_recurrence() {
+        let found = conflicts(vec![
+            event("Standup", "2026-03-02 09:00", "2026-03-02 09:30", DAILY),
+            event("Call", "2026-03-02 09:15", "2026-03-02 09:45", DAILY),
+        ]);
+
+        let expected = ["2026-03-02 09:15", "2026-03-03 09:15", "2026-03-04 09:15"]
+            .map(|start| ("Standup".to_string(), "Call".to_string(), date_time(start)));
+
+        assert_eq!(found, expected);
+    }
+
+    #[test]
+    fn multi_day_event() {
+        let found = conflicts(vec![
+            event("Trip", "2026-03-02 08:00", "2026-03-04 18:00", None),
+            event("Dentist", "2026-03-03 10:00", "2026-03-03 11:00", None),
+            event("Lunch", "2026-03-04 12:00", "2026-03-04 13:00", None),
+            event("Dinner", "2026-03-04 19:00", "2026-03-04 21:00", None),
+        ]);
+
+        assert_eq!(
+            found,
+            [
+                ("Trip", "Dentist", "2026-03-03 10:00"),
+                ("Trip", "Lunch", "2026-03-04 12:00"),
+            ]
+            .map(|(first, second, start)| {
+                (first.to_string(), second.to_string(), date_time(start))
+            })
+        );
+    }
+
+    #[test]
+    fn back_to_back() {
+        let found = conflicts(vec![
+            event("First", "2026-03-02 10:00", "2026-03-02 11:00", None),
+            event("Second", "2026-03-02 11:00", "2026-03-02 12:00", None),
+        ]);
+
+        assert!(found.is_empty());
+    }
+}
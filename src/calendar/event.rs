@@ -5,12 +5,15 @@ use rrule::Unvalidated;
 use url::Url;
 use uuid::Uuid;
 
+use crate::i18n::{self, tr};
+
 #[derive(Debug, Clone)]
 pub struct Event {
     pub etag: String,
     pub uid: Uuid,
     pub summary: String,
     pub description: Option<String>,
+    pub location: Option<String>,
     pub start: NaiveDateTime,
     pub end: NaiveDateTime,
     pub url: Url,
@@ -35,6 +38,15 @@ impl Event {
         )
     }
 
+    /// Starts at midnight and lasts whole days
+    pub fn is_all_day(&self) -> bool {
+        let delta = self.end - self.start;
+
+        self.start.time() == chrono::NaiveTime::MIN
+            && !delta.is_zero()
+            && delta.num_days() * 86400 == delta.num_seconds()
+    }
+
     pub const fn start_date(&self) -> NaiveDate {
         self.start.date()
     }
@@ -59,24 +71,39 @@ impl Event {
         (self.start_date(), self.end_date())
     }
 
-    pub fn all_date_times(&self) -> BTreeSet<NaiveDateTime> {
+    /// Start of every occurrence, recurring events until a year from now
+    pub fn occurrence_starts(&self) -> BTreeSet<NaiveDateTime> {
         self.rrule.as_ref().map_or_else(
-            || dates_between(self.start, self.end),
+            || [self.start].into(),
             |rrule| {
                 let limit = chrono::Utc::now().date_naive() + chrono::Duration::days(365 + 2);
 
-                let interval = self.end - self.start;
                 let start = self.start.and_utc().with_timezone(&rrule::Tz::LOCAL);
                 let set = rrule.clone().build(start).unwrap();
 
                 set.into_iter()
                     .map(|date| date.naive_utc())
                     .take_while(move |start| start.date() < limit)
-                    .flat_map(move |start| dates_between(start, start + interval))
                     .collect()
             },
         )
     }
+
+    /// Every day of the occurrence starting at `start`, at its start time
+    pub fn occurrence_date_times(&self, start: NaiveDateTime) -> BTreeSet<NaiveDateTime> {
+        dates_between(start, start + (self.end - self.start))
+    }
+
+    /// Last day of the occurrence starting at `start`
+    pub fn occurrence_end_date(&self, start: NaiveDateTime) -> NaiveDate {
+        let end = start + (self.end - self.start);
+
+        if end > start && end.hour() == 0 && end.minute() == 0 {
+            end.date() - Days::new(1)
+        } else {
+            end.date()
+        }
+    }
 }
 
 impl PartialEq for Event {
@@ -101,11 +128,11 @@ fn dates_between(start: NaiveDateTime, end: NaiveDateTime) -> BTreeSet<NaiveDate
 fn format_date(date: &DateTime<Local>) -> String {
     let now = Local::now();
 
-    let formatted = if date.year() == now.year() {
-        date.format_localized("%m. %b %H:%M", chrono::Locale::de_DE)
+    let format = if date.year() == now.year() {
+        tr("format-event-date")
     } else {
-        date.format_localized("%m. %b %Y %H:%M", chrono::Locale::de_DE)
+        tr("format-event-date-year")
     };
 
-    formatted.to_string()
+    date.format_localized(&format, i18n::locale()).to_string()
 }
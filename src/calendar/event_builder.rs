@@ -8,6 +8,7 @@ use url::Url;
 use uuid::Uuid;
 
 use super::{event::Event, extract};
+use crate::i18n::tr;
 
 #[derive(Debug)]
 pub enum EventBuilderError {
@@ -32,6 +33,7 @@ pub struct EventBuilder {
     pub uid: Option<String>,
     pub summary: Option<String>,
     pub description: Option<String>,
+    pub location: Option<String>,
     pub start: Option<DatePerhapsTime>,
     pub end: Option<DatePerhapsTime>,
     pub url: Option<String>,
@@ -48,7 +50,7 @@ impl EventBuilder {
         let uid_str = self.uid.ok_or(EventBuilderError::NoUid)?;
         let uid: Uuid = Uuid::parse_str(&uid_str)
             .map_err(|err| EventBuilderError::InvalidUid(err.to_string()))?;
-        let summary = self.summary.unwrap_or_else(|| "<kein Titel>".to_owned());
+        let summary = self.summary.unwrap_or_else(|| tr("event-no-title"));
         let start = self.start.ok_or(EventBuilderError::NoStart)?;
         let start = date_perhaps_time_to_date_time(start).ok_or(EventBuilderError::InvalidStart)?;
         let end = self.end.ok_or(EventBuilderError::NoEnd)?;
@@ -70,6 +72,7 @@ impl EventBuilder {
             uid,
             summary,
             description: self.description,
+            location: self.location,
             start,
             end,
             url,
@@ -97,6 +100,11 @@ impl EventBuilder {
         self
     }
 
+    pub fn set_location_opt(mut self, location: Option<String>) -> Self {
+        self.location = location;
+        self
+    }
+
     pub fn set_start_opt(mut self, start: Option<DatePerhapsTime>) -> Self {
         self.start = start;
         self
@@ -131,6 +139,11 @@ impl From<&icalendar::Event> for EventBuilder {
         Self::default()
             .set_summary_opt(event.get_summary().map(std::borrow::ToOwned::to_owned))
             .set_description_opt(event.get_description().map(std::borrow::ToOwned::to_owned))
+            .set_location_opt(
+                event
+                    .property_value("LOCATION")
+                    .map(std::borrow::ToOwned::to_owned),
+            )
             .set_start_opt(event.get_start())
             .set_end_opt(event.get_end())
             .set_uid_opt(event.get_uid().map(std::borrow::ToOwned::to_owned))
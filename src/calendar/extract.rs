@@ -23,6 +23,15 @@ pub fn event_data(element: &xmltree::Element) -> Option<String> {
         .map(|e| e.to_string())
 }
 
+pub fn address_data(element: &xmltree::Element) -> Option<String> {
+    element
+        .get_child("propstat")
+        .and_then(|e| e.get_child("prop"))
+        .and_then(|e| e.get_child("address-data"))
+        .and_then(xmltree::Element::get_text)
+        .map(|e| e.to_string())
+}
+
 pub fn calendar_name(element: &xmltree::Element) -> Option<String> {
     element
         .get_child("propstat")
@@ -49,6 +58,14 @@ pub fn is_calendar(element: &xmltree::Element) -> bool {
         .is_some_and(|e| e.get_child("calendar").is_some())
 }
 
+pub fn is_addressbook(element: &xmltree::Element) -> bool {
+    element
+        .get_child("propstat")
+        .and_then(|e| e.get_child("prop"))
+        .and_then(|e| e.get_child("resourcetype"))
+        .is_some_and(|e| e.get_child("addressbook").is_some())
+}
+
 pub fn calendar_supports_vevents(element: &xmltree::Element) -> bool {
     element
         .get_child("propstat")
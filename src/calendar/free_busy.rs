@@ -0,0 +1,212 @@
+use chrono::{NaiveDateTime, TimeDelta};
+use uuid::Uuid;
+
+use super::caldav::{Client, TimeRangeFilter};
+use super::calendar::DEFAULT_COLOR;
+use super::{Calendar, CalendarKind, CalendarMap, Event, IndicatorShape};
+use crate::config;
+use crate::i18n::tr;
+
+/// Add one calendar of busy blocks per configured free/busy calendar to the map.
+pub fn add_to_map(
+    client: &Client,
+    configs: &[config::FreeBusy],
+    map: &mut CalendarMap,
+    time_range: &TimeRangeFilter,
+) {
+    for config in configs {
+        // Derived from the path like the uid of calendars listed by the server
+        let uid = Uuid::new_v5(&Uuid::NAMESPACE_URL, config.url.path().as_bytes());
+
+        if map.calendars().contains_key(&uid) {
+            log::info!(
+                "Calendar: \"{}\" is loaded with its events, skipping free/busy",
+                config.name
+            );
+            continue;
+        }
+
+        let periods = match client.get_free_busy(&config.url, time_range) {
+            Ok(periods) => periods,
+            Err(err) => {
+                log::error!(
+                    "Calendar: failed to fetch free/busy of \"{}\": {err:?}",
+                    config.name
+                );
+                continue;
+            }
+        };
+
+        let color = config.color.or_else(|| {
+            client
+                .get_calendar_color(&config.url)
+                .inspect_err(|err| {
+                    log::warn!(
+                        "Calendar: failed to fetch color of \"{}\": {err:?}",
+                        config.name
+                    );
+                })
+                .ok()
+                .flatten()
+        });
+
+        let calendar = Calendar {
+            uid,
+            url_str: config.url.to_string(),
+            name: config.name.clone(),
+            color: color.unwrap_or(DEFAULT_COLOR),
+            kind: CalendarKind::FreeBusy,
+            shape: IndicatorShape::default(),
+        };
+
+        for (start, end) in periods {
+            let mut url = config.url.clone();
+            url.set_fragment(Some(&start.and_utc().timestamp().to_string()));
+
+            map.add_event(
+                calendar.uid,
+                Event {
+                    etag: String::new(),
+                    uid: Uuid::new_v5(&Uuid::NAMESPACE_URL, url.as_str().as_bytes()),
+                    summary: tr("free-busy-busy"),
+                    description: None,
+                    location: None,
+                    start,
+                    end,
+                    url,
+                    rrule: None,
+                },
+            );
+        }
+
+        map.add_calendar(calendar);
+    }
+}
+
+/// Busy periods of all `FREEBUSY` properties in a `VFREEBUSY` response, free periods are skipped.
+pub fn parse_free_busy(data: &str) -> Vec<(NaiveDateTime, NaiveDateTime)> {
+    let unfolded = data
+        .replace("\r\n ", "")
+        .replace("\r\n\t", "")
+        .replace("\n ", "")
+        .replace("\n\t", "");
+
+    unfolded
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .filter(|(property, _)| {
+            let mut params = property.split(';');
+
+            params
+                .next()
+                .is_some_and(|name| name.eq_ignore_ascii_case("FREEBUSY"))
+                && !params.any(|param| param.eq_ignore_ascii_case("FBTYPE=FREE"))
+        })
+        .flat_map(|(_, value)| value.trim().split(','))
+        .filter_map(|period| {
+            let (start, end) = period.split_once('/')?;
+            let start = parse_date_time(start)?;
+
+            let end = if end.starts_with(['P', '+']) {
+                start + parse_duration(end)?
+            } else {
+                parse_date_time(end)?
+            };
+
+            Some((start, end))
+        })
+        .collect()
+}
+
+/// `19970308T160000Z`, periods are always in UTC
+fn parse_date_time(value: &str) -> Option<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(value.trim_end_matches('Z'), "%Y%m%dT%H%M%S").ok()
+}
+
+/// `PT8H30M`, `P1D` or `P1W`, durations without units or with units out of place are invalid
+fn parse_duration(value: &str) -> Option<TimeDelta> {
+    let value = value.trim_start_matches('+').strip_prefix('P')?;
+
+    let mut duration = TimeDelta::zero();
+    let mut number = String::new();
+    let mut time = false;
+    let mut units = 0;
+
+    for c in value.chars() {
+        match c {
+            '0'..='9' => number.push(c),
+            'T' if !time && number.is_empty() => time = true,
+            unit => {
+                let n: i64 = std::mem::take(&mut number).parse().ok()?;
+
+                duration += match (unit, time) {
+                    ('W', false) => TimeDelta::weeks(n),
+                    ('D', false) => TimeDelta::days(n),
+                    ('H', true) => TimeDelta::hours(n),
+                    ('M', true) => TimeDelta::minutes(n),
+                    ('S', true) => TimeDelta::seconds(n),
+                    _ => return None,
+                };
+                units += 1;
+            }
+        }
+    }
+
+    (number.is_empty() && units > 0).then_some(duration)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date_time(value: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    #[test]
+    fn period_lists() {
+        let data = "BEGIN:VFREEBUSY\r\n\
+            FREEBUSY:20260302T080000Z/20260302T090000Z,20260302T100000Z/2026030\r\n \
+            2T113000Z\r\n\
+            FREEBUSY;FBTYPE=BUSY-TENTATIVE:20260303T120000Z/20260303T130000Z\r\n\
+            FREEBUSY;FBTYPE=FREE:20260304T080000Z/20260304T170000Z\r\n\
+            END:VFREEBUSY\r\n";
+
+        assert_eq!(
+            parse_free_busy(data),
+            [
+                (date_time("2026-03-02 08:00"), date_time("2026-03-02 09:00")),
+                (date_time("2026-03-02 10:00"), date_time("2026-03-02 11:30")),
+                (date_time("2026-03-03 12:00"), date_time("2026-03-03 13:00")),
+            ]
+        );
+    }
+
+    #[test]
+    fn start_duration_periods() {
+        let data = "FREEBUSY:20260302T080000Z/PT1H30M,20260305T000000Z/P1D\n\
+            FREEBUSY:20260310T080000Z/P1W,20260320T080000Z/PT\n";
+
+        assert_eq!(
+            parse_free_busy(data),
+            [
+                (date_time("2026-03-02 08:00"), date_time("2026-03-02 09:30")),
+                (date_time("2026-03-05 00:00"), date_time("2026-03-06 00:00")),
+                (date_time("2026-03-10 08:00"), date_time("2026-03-17 08:00")),
+            ]
+        );
+    }
+
+    #[test]
+    fn durations() {
+        assert_eq!(parse_duration("PT8H30M"), Some(TimeDelta::minutes(510)));
+        assert_eq!(parse_duration("+P1DT2H"), Some(TimeDelta::hours(26)));
+        assert_eq!(parse_duration("PT45S"), Some(TimeDelta::seconds(45)));
+
+        for malformed in [
+            "", "1H", "P", "PT", "PT8", "P1H", "P1M", "PT1D", "PXD", "P1DTT1H", "-PT1H",
+        ] {
+            assert_eq!(parse_duration(malformed), None, "{malformed}");
+        }
+    }
+}
@@ -0,0 +1,299 @@
+use std::path::Path;
+
+use chrono::{Datelike, Days, NaiveDate, NaiveTime};
+use palette::rgb::Rgb;
+use url::Url;
+use uuid::Uuid;
+
+use super::event_builder::EventBuilder;
+use super::{Calendar, CalendarKind, CalendarMap, Event, IndicatorShape};
+use crate::config;
+use crate::i18n::tr;
+
+enum Rule {
+    /// Fixed day and month
+    Fixed(u32, u32),
+    /// Days relative to easter sunday
+    Easter(i64),
+    /// Wednesday before the 23th of november
+    RepentanceDay,
+}
+
+struct Holiday {
+    name: &'static str,
+    rule: Rule,
+    /// States the holiday applies to, nationwide if empty
+    states: &'static [&'static str],
+}
+
+const fn holiday(name: &'static str, rule: Rule, states: &'static [&'static str]) -> Holiday {
+    Holiday { name, rule, states }
+}
+
+static DE: &[Holiday] = &[
+    holiday("Neujahr", Rule::Fixed(1, 1), &[]),
+    holiday("Heilige Drei Könige", Rule::Fixed(1, 6), &["BW", "BY", "ST"]),
+    holiday("Internationaler Frauentag", Rule::Fixed(3, 8), &["BE", "MV"]),
+    holiday("Karfreitag", Rule::Easter(-2), &[]),
+    holiday("Ostersonntag", Rule::Easter(0), &["BB"]),
+    holiday("Ostermontag", Rule::Easter(1), &[]),
+    holiday("Tag der Arbeit", Rule::Fixed(5, 1), &[]),
+    holiday("Christi Himmelfahrt", Rule::Easter(39), &[]),
+    holiday("Pfingstsonntag", Rule::Easter(49), &["BB"]),
+    holiday("Pfingstmontag", Rule::Easter(50), &[]),
+    holiday("Fronleichnam", Rule::Easter(60), &["BW", "BY", "HE", "NW", "RP", "SL"]),
+    holiday("Mariä Himmelfahrt", Rule::Fixed(8, 15), &["SL"]),
+    holiday("Weltkindertag", Rule::Fixed(9, 20), &["TH"]),
+    holiday("Tag der Deutschen Einheit", Rule::Fixed(10, 3), &[]),
+    holiday(
+        "Reformationstag",
+        Rule::Fixed(10, 31),
+        &["BB", "HB", "HH", "MV", "NI", "SN", "ST", "SH", "TH"],
+    ),
+    holiday("Allerheiligen", Rule::Fixed(11, 1), &["BW", "BY", "NW", "RP", "SL"]),
+    holiday("Buß- und Bettag", Rule::RepentanceDay, &["SN"]),
+    holiday("1. Weihnachtstag", Rule::Fixed(12, 25), &[]),
+    holiday("2. Weihnachtstag", Rule::Fixed(12, 26), &[]),
+];
+
+static AT: &[Holiday] = &[
+    holiday("Neujahr", Rule::Fixed(1, 1), &[]),
+    holiday("Heilige Drei Könige", Rule::Fixed(1, 6), &[]),
+    holiday("Ostermontag", Rule::Easter(1), &[]),
+    holiday("Staatsfeiertag", Rule::Fixed(5, 1), &[]),
+    holiday("Christi Himmelfahrt", Rule::Easter(39), &[]),
+    holiday("Pfingstmontag", Rule::Easter(50), &[]),
+    holiday("Fronleichnam", Rule::Easter(60), &[]),
+    holiday("Mariä Himmelfahrt", Rule::Fixed(8, 15), &[]),
+    holiday("Nationalfeiertag", Rule::Fixed(10, 26), &[]),
+    holiday("Allerheiligen", Rule::Fixed(11, 1), &[]),
+    holiday("Mariä Empfängnis", Rule::Fixed(12, 8), &[]),
+    holiday("Christtag", Rule::Fixed(12, 25), &[]),
+    holiday("Stefanitag", Rule::Fixed(12, 26), &[]),
+];
+
+fn holidays_of(country: &str) -> Option<&'static [Holiday]> {
+    match country.to_ascii_uppercase().as_str() {
+        "DE" => Some(DE),
+        "AT" => Some(AT),
+        _ => None,
+    }
+}
+
+/// Add the configured public holidays and school vacations as their own calendars to the map.
+pub fn add_to_map(config: &config::Holidays, map: &mut CalendarMap, start: NaiveDate, end: NaiveDate) {
+    match holidays_of(&config.country) {
+        Some(holidays) => {
+            let calendar = holiday_calendar(config);
+
+            for year in start.year()..=end.year() {
+                for (date, name) in holidays_in(holidays, config.state.as_deref(), year) {
+                    map.add_event(calendar.uid, all_day_event(&calendar, date, name));
+                }
+            }
+
+            map.add_calendar(calendar);
+        }
+        None => log::error!("Holidays: unsupported country \"{}\"", config.country),
+    }
+
+    if let Some(path) = &config.school_vacations {
+        match school_vacations(path) {
+            Ok((calendar, events)) => {
+                for event in events {
+                    map.add_event(calendar.uid, event);
+                }
+
+                map.add_calendar(calendar);
+            }
+            Err(err) => log::error!("Holidays: failed to load school vacations: {err}"),
+        }
+    }
+}
+
+fn holidays_in(
+    holidays: &'static [Holiday],
+    state: Option<&str>,
+    year: i32,
+) -> impl Iterator<Item = (NaiveDate, &'static str)> {
+    let state = state.map(str::to_ascii_uppercase);
+
+    holidays
+        .iter()
+        .filter(move |holiday| {
+            holiday.states.is_empty()
+                || state
+                    .as_deref()
+                    .is_some_and(|state| holiday.states.contains(&state))
+        })
+        .filter_map(move |holiday| {
+            let date = match holiday.rule {
+                Rule::Fixed(month, day) => NaiveDate::from_ymd_opt(year, month, day)?,
+                Rule::Easter(offset) => easter_sunday(year)? + chrono::Duration::days(offset),
+                Rule::RepentanceDay => repentance_day(year)?,
+            };
+
+            Some((date, holiday.name))
+        })
+}
+
+/// Easter sunday of the gregorian calendar (anonymous gregorian algorithm)
+fn easter_sunday(year: i32) -> Option<NaiveDate> {
+    let a = year % 19;
+    let b = year / 100;
+    let c = year % 100;
+    let d = b / 4;
+    let e = b % 4;
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let i = c / 4;
+    let k = c % 4;
+    let l = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m = (a + 11 * h + 22 * l) / 451;
+    let month = (h + l - 7 * m + 114) / 31;
+    let day = (h + l - 7 * m + 114) % 31 + 1;
+
+    NaiveDate::from_ymd_opt(year, month as u32, day as u32)
+}
+
+/// Buß- und Bettag, the last wednesday before the 23th of november
+fn repentance_day(year: i32) -> Option<NaiveDate> {
+    let mut date = NaiveDate::from_ymd_opt(year, 11, 22)?;
+    while date.weekday() != chrono::Weekday::Wed {
+        date = date.pred_opt()?;
+    }
+
+    Some(date)
+}
+
+fn holiday_calendar(config: &config::Holidays) -> Calendar {
+    let url_str = match &config.state {
+        Some(state) => format!("holidays:{}-{}", config.country, state),
+        None => format!("holidays:{}", config.country),
+    }
+    .to_ascii_lowercase();
+
+    Calendar {
+        uid: Uuid::new_v5(&Uuid::NAMESPACE_URL, url_str.as_bytes()),
+        url_str,
+        name: tr("calendar-holidays"),
+        color: Rgb::new(220, 38, 38),
+        kind: CalendarKind::Holidays,
+        shape: IndicatorShape::default(),
+    }
+}
+
+fn all_day_event(calendar: &Calendar, date: NaiveDate, name: &str) -> Event {
+    let url = format!("{}/{date}", calendar.url_str);
+
+    Event {
+        etag: String::new(),
+        uid: Uuid::new_v5(&Uuid::NAMESPACE_URL, url.as_bytes()),
+        summary: name.to_owned(),
+        description: None,
+        location: None,
+        start: date.and_time(NaiveTime::MIN),
+        end: (date + Days::new(1)).and_time(NaiveTime::MIN),
+        url: Url::parse(&url).expect("Holiday url is valid"),
+        rrule: None,
+    }
+}
+
+fn school_vacations(path: &Path) -> Result<(Calendar, Vec<Event>), String> {
+    let data = std::fs::read_to_string(path).map_err(|err| err.to_string())?;
+    let calendar = icalendar::parser::read_calendar(&data).map(icalendar::Calendar::from)?;
+
+    let url_str = format!("school-vacations:{}", path.display());
+    let url = Url::parse(&url_str).map_err(|err| err.to_string())?;
+
+    let vacations = Calendar {
+        uid: Uuid::new_v5(&Uuid::NAMESPACE_URL, url_str.as_bytes()),
+        url_str,
+        name: tr("calendar-school-vacations"),
+        color: Rgb::new(22, 163, 74),
+        kind: CalendarKind::SchoolVacations,
+        shape: IndicatorShape::default(),
+    };
+
+    let events = calendar
+        .components
+        .iter()
+        .filter_map(|component| match component {
+            icalendar::CalendarComponent::Event(event) => Some(event),
+            _ => None,
+        })
+        .filter_map(|event| {
+            use icalendar::Component as _;
+
+            // Foreign UIDs are rarely UUIDs, derive one from them
+            let uid = event
+                .get_uid()
+                .map(|uid| Uuid::new_v5(&Uuid::NAMESPACE_URL, uid.as_bytes()).to_string());
+
+            let result = EventBuilder::from(event)
+                .set_uid_opt(uid)
+                .set_etag_opt(Some(String::new()))
+                .set_url_opt(Some(url.to_string()))
+                .build();
+
+            match result {
+                Ok(event) => Some(event),
+                Err(err) => {
+                    log::warn!("Holidays: skipping school vacation: {err:?}");
+                    None
+                }
+            }
+        })
+        .collect();
+
+    Ok((vacations, events))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn easter() {
+        for expected in [
+            date(1818, 3, 22),
+            date(2000, 4, 23),
+            date(2019, 4, 21),
+            date(2024, 3, 31),
+            date(2025, 4, 20),
+            date(2026, 4, 5),
+            date(2038, 4, 25),
+        ] {
+            assert_eq!(easter_sunday(expected.year()), Some(expected));
+        }
+    }
+
+    #[test]
+    fn repentance() {
+        for expected in [
+            date(2021, 11, 17),
+            date(2022, 11, 16),
+            date(2023, 11, 22),
+            date(2024, 11, 20),
+            date(2025, 11, 19),
+        ] {
+            assert_eq!(repentance_day(expected.year()), Some(expected));
+        }
+    }
+
+    #[test]
+    fn states() {
+        let saxony: Vec<_> = holidays_in(DE, Some("sn"), 2024).collect();
+        assert!(saxony.contains(&(date(2024, 3, 29), "Karfreitag")));
+        assert!(saxony.contains(&(date(2024, 11, 20), "Buß- und Bettag")));
+
+        let nationwide: Vec<_> = holidays_in(DE, None, 2024).collect();
+        assert!(nationwide.contains(&(date(2024, 5, 9), "Christi Himmelfahrt")));
+        assert!(!nationwide.iter().any(|(_, name)| *name == "Buß- und Bettag"));
+    }
+}
@@ -1,34 +1,157 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+
 use chrono::{NaiveDate, NaiveDateTime};
 use uuid::Uuid;
 
 use crate::config::{self, UuidFilter};
+use crate::state::{self, State};
 
 use super::map::CalendarMap;
-use super::{Calendar, Event};
+use super::conflicts::{self, Conflict};
+use super::{Calendar, CalendarKind, Event, IndicatorShape, carddav, free_busy, holidays};
+
+use super::caldav::{Client, Credentials, Error, TimeRangeFilter};
+
+/// Everything a calendar map is built from, cloned into the fetching thread
+#[derive(Debug, Clone)]
+pub struct Sources {
+    pub client: Client,
+    pub holidays: Option<config::Holidays>,
+    pub contacts: Option<carddav::Client>,
+    pub free_busy: Vec<config::FreeBusy>,
+}
+
+impl Sources {
+    /// Fetch the calendars and add the generated calendars.
+    ///
+    /// # Errors
+    /// Returns an error if fetching the `CalDAV` calendars fails.
+    pub fn fetch(&self) -> Result<CalendarMap, Error> {
+        let mut map = self.client.get_map()?;
+        let time_range = TimeRangeFilter::now();
+
+        if let Some(holidays) = &self.holidays {
+            holidays::add_to_map(holidays, &mut map, time_range.start(), time_range.end());
+        }
+
+        free_busy::add_to_map(&self.client, &self.free_busy, &mut map, &time_range);
 
-use super::caldav::{Client, Credentials};
+        if let Some(contacts) = &self.contacts
+            && let Err(err) = contacts.add_to_map(&mut map, time_range.start(), time_range.end())
+        {
+            log::error!("Calendar: failed to fetch birthdays: {err:?}");
+        }
+
+        Ok(map)
+    }
+}
 
 #[derive(Debug)]
 pub struct Manager {
-    pub client: Client,
+    pub sources: Sources,
     map: CalendarMap,
+    // Visibility of calendars without a toggled state
+    visible: Option<UuidFilter>,
+    colors: config::Colors,
+    state: State,
+    state_path: Option<PathBuf>,
 }
 
 impl Manager {
-    pub fn new(ical: config::Ical) -> Self {
+    pub fn new(config: &config::Config) -> Self {
+        let ical = config.ical.clone();
+        let state_path = config.state_file.clone().or_else(state::default_path);
+
         Self {
-            client: Client::new(ical.url.clone(), Credentials::from(ical)),
+            sources: Sources {
+                client: Client::new(ical.url.clone(), Credentials::from(ical)),
+                holidays: config.holidays.clone(),
+                contacts: config.contacts.clone().map(|contacts| {
+                    carddav::Client::new(contacts.url.clone(), Credentials::from(contacts))
+                }),
+                free_busy: config.free_busy.clone(),
+            },
             map: CalendarMap::default(),
+            visible: config.calendar.visible.clone(),
+            colors: config.colors.clone(),
+            state: state_path.as_deref().map(State::load).unwrap_or_default(),
+            state_path,
         }
     }
 
     // Returns true if the given map is different from the current map
-    pub fn set_map(&mut self, map: CalendarMap) -> bool {
+    pub fn set_map(&mut self, mut map: CalendarMap) -> bool {
+        let visibility: Vec<(Uuid, bool)> = map
+            .calendars()
+            .values()
+            .map(|(_, calendar)| (calendar.uid, self.is_visible(calendar)))
+            .collect();
+
+        for (uid, visible) in visibility {
+            map.set_enabled(&uid, visible);
+        }
+
+        // Generated calendars keep their fixed colors
+        let slots = palette_slots(
+            map.calendars()
+                .values()
+                .filter(|(_, calendar)| calendar.kind == CalendarKind::CalDav)
+                .map(|(_, calendar)| calendar.uid),
+            self.colors
+                .palette
+                .map_or(IndicatorShape::ALL.len(), |palette| palette.colors().len()),
+        );
+
+        for calendar in map.calendars_mut() {
+            self.apply_colors(slots.get(&calendar.uid).copied(), calendar);
+        }
+
         let old_map = std::mem::replace(&mut self.map, map);
 
         old_map != self.map
     }
 
+    fn apply_colors(&self, slot: Option<usize>, calendar: &mut Calendar) {
+        if let Some(slot) = slot {
+            if let Some(palette) = self.colors.palette {
+                let colors = palette.colors();
+                calendar.color = colors[slot % colors.len()];
+            }
+
+            if self.colors.shapes {
+                calendar.shape = IndicatorShape::ALL[slot % IndicatorShape::ALL.len()];
+            }
+        }
+
+        for color_override in self
+            .colors
+            .overrides
+            .iter()
+            .filter(|color_override| color_override.calendar.matches(&calendar.uid, &calendar.name))
+        {
+            if let Some(color) = color_override.color {
+                calendar.color = color;
+            }
+
+            if let Some(shape) = color_override.shape {
+                calendar.shape = shape;
+            }
+        }
+    }
+
+    fn is_visible(&self, calendar: &Calendar) -> bool {
+        self.state
+            .calendar_visibility
+            .get(&calendar.uid)
+            .copied()
+            .unwrap_or_else(|| {
+                self.visible
+                    .as_ref()
+                    .is_none_or(|filter| filter.is_included(&calendar.uid, &calendar.name))
+            })
+    }
+
     // Returns true if the fetched map is different from the current map
     // pub fn update(&mut self) -> bool {
     //     match self.client.get_map() {
@@ -47,11 +170,23 @@ impl Manager {
         self.map
             .calendars()
             .iter()
-            .filter(move |(uid, _)| filter.is_none_or(|filter| filter.is_included(uid)))
+            .filter(move |(uid, (_, calendar))| {
+                filter.is_none_or(|filter| filter.is_included(uid, &calendar.name))
+            })
     }
 
+    /// Toggle the visibility of a calendar and persist it across restarts and refreshes.
     pub fn toggle_calendar(&mut self, uid: Uuid) {
-        self.map.toggle_calendar(uid);
+        let Some(enabled) = self.map.is_enabled(&uid).map(|enabled| !enabled) else {
+            return;
+        };
+
+        self.map.set_enabled(&uid, enabled);
+        self.state.calendar_visibility.insert(uid, enabled);
+
+        if let Some(path) = &self.state_path {
+            self.state.save(path);
+        }
     }
 
     pub fn events_between<'a>(
@@ -63,10 +198,52 @@ impl Manager {
         self.map
             .events_between(start, end)
             .filter(move |(calendar, _, _)| {
-                filter.is_none_or(|filter| filter.is_included(&calendar.uid))
+                filter.is_none_or(|filter| filter.is_included(&calendar.uid, &calendar.name))
             })
     }
 
+    pub fn occurrence_start(
+        &self,
+        uid: &Uuid,
+        date_time: &NaiveDateTime,
+    ) -> Option<&NaiveDateTime> {
+        self.map.occurrence_start(uid, date_time)
+    }
+
+    /// Overlapping timed events starting between the given dates.
+    pub fn conflicts_between<'a>(
+        &'a self,
+        start: NaiveDate,
+        end: NaiveDate,
+        filter: Option<&'a UuidFilter>,
+    ) -> Vec<Conflict<'a>> {
+        // Events of the previous day may last until the given start
+        let lookbehind = start.pred_opt().unwrap_or(start);
+
+        let occurrences = self
+            .map
+            .occurrences_between(lookbehind, end)
+            .filter(move |(calendar, _, _)| {
+                filter.is_none_or(|filter| filter.is_included(&calendar.uid, &calendar.name))
+            });
+
+        conflicts::find(occurrences)
+            .into_iter()
+            .filter(|conflict| conflict.start.date() >= start)
+            .collect()
+    }
+
+    pub fn search<'a>(
+        &'a self,
+        query: &str,
+        from: NaiveDate,
+        filter: Option<&'a UuidFilter>,
+    ) -> impl Iterator<Item = (&'a Calendar, &'a NaiveDateTime, &'a Event)> {
+        self.map.search(query, from).filter(move |(calendar, _, _)| {
+            filter.is_none_or(|filter| filter.is_included(&calendar.uid, &calendar.name))
+        })
+    }
+
     pub fn calendars_between<'a>(
         &'a self,
         start: NaiveDate,
@@ -76,7 +253,7 @@ impl Manager {
         self.map
             .calendars_between(start, end)
             .filter(move |(_, calendar)| {
-                filter.is_none_or(|filter| filter.is_included(&calendar.uid))
+                filter.is_none_or(|filter| filter.is_included(&calendar.uid, &calendar.name))
             })
     }
 
@@ -84,3 +261,57 @@ impl Manager {
         self.map.calendars().get(uid).map(|(_, calendar)| calendar.name.to_string())
     }
 }
+
+/// Palette slots by calendar, derived from the uid so they stay put when other calendars are
+/// added or removed. Taken slots are passed on to the next free one, in the order of the uids.
+fn palette_slots(uids: impl Iterator<Item = Uuid>, len: usize) -> BTreeMap<Uuid, usize> {
+    let mut used = vec![false; len];
+    let mut slots = BTreeMap::new();
+
+    for uid in uids.collect::<BTreeSet<_>>() {
+        let preferred = (uid.as_u128() % len as u128) as usize;
+        let slot = (0..len)
+            .map(|offset| (preferred + offset) % len)
+            .find(|slot| !used[*slot])
+            .unwrap_or(preferred);
+
+        used[slot] = true;
+        slots.insert(uid, slot);
+    }
+
+    slots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slots_follow_the_uid() {
+        let slots = palette_slots([Uuid::from_u128(9), Uuid::from_u128(3)].into_iter(), 7);
+
+        assert_eq!(
+            slots,
+            BTreeMap::from([(Uuid::from_u128(3), 3), (Uuid::from_u128(9), 2)])
+        );
+    }
+
+    #[test]
+    fn slots_are_stable() {
+        let before = palette_slots((1..=3).map(Uuid::from_u128), 7);
+        let after = palette_slots((1..=5).map(Uuid::from_u128), 7);
+
+        for (uid, slot) in before {
+            assert_eq!(after[&uid], slot);
+        }
+    }
+
+    #[test]
+    fn taken_slots_move_on() {
+        let slots = palette_slots([8, 1, 15].into_iter().map(Uuid::from_u128), 7);
+
+        assert_eq!(slots[&Uuid::from_u128(1)], 1);
+        assert_eq!(slots[&Uuid::from_u128(8)], 2);
+        assert_eq!(slots[&Uuid::from_u128(15)], 3);
+    }
+}
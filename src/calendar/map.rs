@@ -1,5 +1,5 @@
-use std::collections::{BTreeMap, BTreeSet};
-use std::ops::Range;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::ops::{Bound, Range};
 
 use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
 use uuid::Uuid;
@@ -12,10 +12,16 @@ pub struct CalendarMap {
     calendars: BTreeMap<Uuid, (bool, Calendar)>,
     // Map of events and their calendar_uid by their uid
     events: BTreeMap<Uuid, (Uuid, Event)>,
+    // Map of occurrence starts by event uid
+    occurrences: BTreeMap<Uuid, BTreeSet<NaiveDateTime>>,
     // Map of dates to events and their start time
     event_map: BTreeMap<NaiveDateTime, BTreeSet<Uuid>>,
     // Map of dates to events and their start time
     calendar_map: BTreeMap<NaiveDateTime, BTreeSet<Uuid>>,
+    // Map of lowercase words in summary, description and location to event uids
+    index: BTreeMap<String, BTreeSet<Uuid>>,
+    // Map of pairs of consecutive letters to the words of the index containing them
+    bigrams: BTreeMap<(char, char), BTreeSet<String>>,
 }
 
 impl CalendarMap {
@@ -23,16 +29,27 @@ impl CalendarMap {
         &self.calendars
     }
 
-    pub fn toggle_calendar(&mut self, uid: Uuid) {
-        if let Some((enabled, _)) = self.calendars.get_mut(&uid) {
-            *enabled = !*enabled;
+    pub fn calendars_mut(&mut self) -> impl Iterator<Item = &mut Calendar> {
+        self.calendars.values_mut().map(|(_, calendar)| calendar)
+    }
+
+    pub fn is_enabled(&self, uid: &Uuid) -> Option<bool> {
+        self.calendars.get(uid).map(|(enabled, _)| *enabled)
+    }
+
+    pub fn set_enabled(&mut self, uid: &Uuid, enabled: bool) {
+        if let Some((current, _)) = self.calendars.get_mut(uid) {
+            *current = enabled;
         }
     }
 
     pub fn clear(&mut self) {
         self.calendars.clear();
         self.events.clear();
+        self.occurrences.clear();
         self.event_map.clear();
+        self.index.clear();
+        self.bigrams.clear();
     }
 
     pub fn add_calendar(&mut self, calendar: Calendar) {
@@ -40,7 +57,12 @@ impl CalendarMap {
     }
 
     pub fn add_event(&mut self, calendar_uid: Uuid, event: Event) {
-        for date_time in event.all_date_times() {
+        let starts = event.occurrence_starts();
+
+        for date_time in starts
+            .iter()
+            .flat_map(|start| event.occurrence_date_times(*start))
+        {
             self.event_map
                 .entry(date_time)
                 .or_default()
@@ -52,9 +74,124 @@ impl CalendarMap {
                 .insert(calendar_uid);
         }
 
+        let text = [
+            Some(event.summary.as_str()),
+            event.description.as_deref(),
+            event.location.as_deref(),
+        ];
+
+        for word in text.into_iter().flatten().flat_map(words) {
+            for bigram in bigrams(&word) {
+                self.bigrams.entry(bigram).or_default().insert(word.clone());
+            }
+
+            self.index.entry(word).or_default().insert(event.uid);
+        }
+
+        self.occurrences.insert(event.uid, starts);
         self.events.insert(event.uid, (calendar_uid, event));
     }
 
+    /// Start of the occurrence a day of an event belongs to, earlier than the given
+    /// date time on the following days of a multi-day occurrence
+    pub fn occurrence_start(
+        &self,
+        uid: &Uuid,
+        date_time: &NaiveDateTime,
+    ) -> Option<&NaiveDateTime> {
+        self.occurrences.get(uid)?.range(..=date_time).next_back()
+    }
+
+    /// Occurrences starting between the given dates, without the following days of
+    /// multi-day events
+    pub fn occurrences_between(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> impl Iterator<Item = (&Calendar, &NaiveDateTime, &Event)> {
+        self.events_between(start, end)
+            .filter(|(_, date_time, event)| {
+                self.occurrence_start(&event.uid, date_time) == Some(*date_time)
+            })
+    }
+
+    /// Find events containing all words of the query, tolerating typos and partial words.
+    /// Yields the occurrences from the given date on chronologically, multi-day events on their
+    /// first day only, followed by the earlier ones starting with the most recent.
+    pub fn search(
+        &self,
+        query: &str,
+        from: NaiveDate,
+    ) -> impl Iterator<Item = (&Calendar, &NaiveDateTime, &Event)> {
+        let matched = words(query)
+            .map(|word| self.lookup(&word))
+            .reduce(|matched, uids| matched.intersection(&uids).copied().collect())
+            .unwrap_or_default();
+
+        let from = from.and_time(NaiveTime::MIN);
+        let mut seen = HashSet::new();
+
+        self.event_map
+            .range(from..)
+            .chain(self.event_map.range(..from).rev())
+            .flat_map(move |(date_time, id_set)| {
+                id_set
+                    .iter()
+                    .filter(|uid| matched.contains(uid))
+                    .map(move |uid| (date_time, *uid))
+                    .collect::<Vec<_>>()
+            })
+            .filter(move |(date_time, uid)| {
+                let start = self.occurrence_start(uid, date_time);
+
+                // Occurrences going on at `from` are listed on that day
+                (**date_time >= from || start == Some(*date_time)) && seen.insert((*uid, start))
+            })
+            .filter_map(|(date_time, uid)| {
+                let (calendar_uid, event) = self.events.get(&uid).unwrap();
+                let (enabled, calendar) = self.calendars.get(calendar_uid).unwrap();
+
+                enabled.then_some((calendar, date_time, event))
+            })
+    }
+
+    fn lookup(&self, word: &str) -> BTreeSet<Uuid> {
+        let len = word.chars().count();
+        let max_typos = match len {
+            0..=3 => 0,
+            4..=7 => 1,
+            _ => 2,
+        };
+
+        // Tokens starting with the word are next to each other in the index
+        let mut tokens: BTreeSet<&str> = self
+            .index
+            .range::<str, _>((Bound::Included(word), Bound::Unbounded))
+            .map(|(token, _)| token.as_str())
+            .take_while(|token| token.starts_with(word))
+            .collect();
+
+        if max_typos > 0 {
+            // Each typo changes at most two bigrams, so words with up to two typos in at least
+            // four letters still share a bigram with the token or its beginning
+            let candidates: BTreeSet<&str> = bigrams(word)
+                .filter_map(|bigram| self.bigrams.get(&bigram))
+                .flatten()
+                .map(String::as_str)
+                .collect();
+
+            tokens.extend(candidates.into_iter().filter(|token| {
+                levenshtein(word, token) <= max_typos
+                    || levenshtein(word, &prefix(token, len)) <= max_typos
+            }));
+        }
+
+        tokens
+            .into_iter()
+            .flat_map(|token| self.index[token].iter().copied())
+            .collect()
+    }
+
     pub fn events_between(
         &self,
         start: NaiveDate,
@@ -103,6 +240,38 @@ impl CalendarMap {
     }
 }
 
+fn words(text: &str) -> impl Iterator<Item = String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+}
+
+fn bigrams(word: &str) -> impl Iterator<Item = (char, char)> {
+    word.chars().zip(word.chars().skip(1))
+}
+
+fn prefix(word: &str, len: usize) -> String {
+    word.chars().take(len).collect()
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut row = (0..=b.len()).collect::<Vec<_>>();
+
+    for (i, a) in a.chars().enumerate() {
+        let mut previous = row[0];
+        row[0] = i + 1;
+
+        for (j, b) in b.iter().enumerate() {
+            let substitution = previous + usize::from(a != *b);
+            previous = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(previous + 1);
+        }
+    }
+
+    row[b.len()]
+}
+
 fn range(start: NaiveDate, end: NaiveDate) -> Range<NaiveDateTime> {
     start.and_time(NaiveTime::MIN)..(end.and_time(NaiveTime::MIN) + chrono::Duration::days(1))
 }
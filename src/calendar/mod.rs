@@ -1,12 +1,16 @@
 pub mod caldav;
 mod calendar;
+pub mod carddav;
+pub mod conflicts;
 mod event;
 mod event_builder;
 mod extract;
+pub mod free_busy;
+pub mod holidays;
 mod manager;
 mod map;
 
-pub use calendar::Calendar;
+pub use calendar::{Calendar, CalendarKind, IndicatorShape};
 pub use event::Event;
 pub use manager::Manager;
 pub use map::CalendarMap;
@@ -1,3 +1,4 @@
+use std::net::SocketAddr;
 use std::path::PathBuf;
 
 use serde::Deserialize;
@@ -5,17 +6,36 @@ use serde::de::{self, Deserializer};
 use url::Url;
 use uuid::Uuid;
 
+use crate::calendar::{Color, IndicatorShape};
+
 #[derive(Clone, Debug, serde::Deserialize)]
 pub struct Config {
     pub ical: Ical,
+    #[serde(default = "default_locale")]
+    pub locale: String,
     #[serde(default)]
     pub videos: Vec<Video>,
+    pub video_grid: Option<VideoGrid>,
+    pub video_popup: Option<VideoPopup>,
+    pub video_tour: Option<VideoTour>,
+    pub snapshots: Option<Snapshots>,
+    pub events: Option<Events>,
     #[serde(default)]
     pub calendar: Calendars,
     #[serde(default)]
     pub screensaver: Screensaver,
     #[serde(default)]
     pub grafana: Grafana,
+    pub holidays: Option<Holidays>,
+    pub contacts: Option<Contacts>,
+    pub countdown: Option<Countdown>,
+    #[serde(default)]
+    pub colors: Colors,
+    pub conflicts: Option<Conflicts>,
+    #[serde(default)]
+    pub free_busy: Vec<FreeBusy>,
+    /// Where runtime state like toggled calendars is kept, defaults to `$XDG_STATE_HOME`
+    pub state_file: Option<PathBuf>,
 }
 
 #[derive(Clone, serde::Deserialize)]
@@ -36,10 +56,178 @@ impl core::fmt::Debug for Ical {
     }
 }
 
+#[derive(Clone, serde::Deserialize)]
+pub struct Contacts {
+    pub url: Url,
+    pub username: String,
+    #[serde(deserialize_with = "deserialize_from_file_opt")]
+    pub password: String,
+}
+
+impl core::fmt::Debug for Contacts {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Contacts")
+            .field("url", &self.url)
+            .field("username", &self.username)
+            .field("password", &"<hidden>")
+            .finish()
+    }
+}
+
 #[derive(Clone, Debug, serde::Deserialize)]
 pub struct Video {
     pub name: String,
     pub url: Url,
+    /// Placement in the video grid, following the order of the videos if unset
+    pub column: Option<u16>,
+    pub row: Option<u16>,
+    pub width: Option<u16>,
+    pub height: Option<u16>,
+    /// Audio stays disabled if unset
+    pub audio: Option<VideoAudio>,
+    /// Shows pan/tilt/zoom controls if the camera supports ONVIF PTZ
+    pub onvif: Option<Onvif>,
+    /// Milliseconds buffered by RTSP streams, GStreamer defaults to 2000
+    pub latency: Option<u32>,
+    /// Lower transport of RTSP streams
+    pub transport: Option<Transport>,
+    /// Only use hardware decoders where one supports the codec (`true`) or never (`false`),
+    /// GStreamer picks decoders by rank if unset
+    pub hardware_decoding: Option<bool>,
+    /// GStreamer source pipeline used instead of `url`, its unlinked source pads get decoded,
+    /// e.g. `rtspsrc location=rtsp://door/stream latency=0 ! rtph264depay ! h264parse`
+    pub pipeline: Option<String>,
+    /// Camera name used in motion events, defaults to `name`
+    pub event_camera: Option<String>,
+    /// Seconds shown during the tour, overriding the tour default, 0 skips the stream
+    pub dwell: Option<u32>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Transport {
+    Tcp,
+    Udp,
+}
+
+#[derive(Clone, serde::Deserialize)]
+pub struct Onvif {
+    /// Device service, e.g. `http://camera/onvif/device_service`
+    pub url: Url,
+    pub username: String,
+    #[serde(deserialize_with = "deserialize_from_file_opt")]
+    pub password: String,
+    /// Token or name of the media profile, defaults to the first one with PTZ support
+    pub profile: Option<String>,
+    /// Pan, tilt and zoom velocity from 0.0 to 1.0
+    #[serde(default = "default_ptz_speed")]
+    pub speed: f32,
+}
+
+impl core::fmt::Debug for Onvif {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Onvif")
+            .field("url", &self.url)
+            .field("username", &self.username)
+            .field("password", &"<hidden>")
+            .field("profile", &self.profile)
+            .field("speed", &self.speed)
+            .finish()
+    }
+}
+
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct VideoAudio {
+    /// Start muted, audio can be unmuted with the mute button
+    #[serde(default)]
+    pub muted: bool,
+    /// Linear volume, 1.0 is 100%
+    #[serde(default = "default_audio_volume")]
+    pub volume: f64,
+    /// Command spawned while the talk button is held and killed on release. It has to stream
+    /// the microphone to the camera itself, e.g. to its HTTP audio endpoint or through a relay
+    /// like go2rtc. The ONVIF backchannel of RTSP is not spoken by the player yet.
+    #[serde(default)]
+    pub talk: Vec<String>,
+}
+
+/// Rotates through the streams, started with the tour button
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct VideoTour {
+    /// Start rotating right away
+    #[serde(default = "default_tour_enabled")]
+    pub enabled: bool,
+    /// Seconds each stream is shown
+    #[serde(default = "default_tour_dwell")]
+    pub dwell: u32,
+    /// Seconds the tour waits after a stream was selected by hand
+    #[serde(default = "default_tour_pause")]
+    pub pause: u32,
+}
+
+/// Stream shown full size on USR2, e.g. sent by a doorbell integration. Requests to the events
+/// endpoint can show any stream, see [`Events`].
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct VideoPopup {
+    pub name: String,
+    /// Seconds until the normal layout is restored
+    #[serde(default = "default_video_popup_duration")]
+    pub duration: u64,
+    /// Save a snapshot of the stream once it is shown
+    #[serde(default)]
+    pub snapshot: bool,
+}
+
+/// Webhook endpoint for motion events of cameras or NVRs like Frigate or MotionEye, and for
+/// showing a stream full size, e.g. from a doorbell
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct Events {
+    /// e.g. `0.0.0.0:8090`
+    pub listen: SocketAddr,
+    /// Number of events in the history next to the player
+    #[serde(default = "default_events_history")]
+    pub history: usize,
+    /// Seconds a stream stays highlighted after an event
+    #[serde(default = "default_events_highlight")]
+    pub highlight: u32,
+    /// Seconds a stream requested with `/show` is shown, unless the request sets `duration`
+    #[serde(default = "default_video_popup_duration")]
+    pub show_duration: u64,
+}
+
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct Snapshots {
+    pub directory: PathBuf,
+    #[serde(default)]
+    pub format: SnapshotFormat,
+    /// Number of recent snapshots shown in the gallery
+    #[serde(default = "default_gallery_size")]
+    pub gallery_size: usize,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SnapshotFormat {
+    Png,
+    #[default]
+    Jpeg,
+}
+
+impl SnapshotFormat {
+    #[must_use]
+    pub const fn extension(self) -> &'static str {
+        match self {
+            Self::Png => "png",
+            Self::Jpeg => "jpg",
+        }
+    }
+}
+
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct VideoGrid {
+    /// Columns for videos without a placement, e.g. 2 for a 2x2 grid
+    #[serde(default = "default_video_grid_columns")]
+    pub columns: u16,
 }
 
 #[derive(Clone, Debug, Default, serde::Deserialize)]
@@ -50,43 +238,177 @@ pub struct Calendars {
     pub event: Option<UuidFilter>,
     pub ticker: Option<UuidFilter>,
     pub week: Option<UuidFilter>,
+    pub year: Option<UuidFilter>,
+    pub search: Option<UuidFilter>,
     pub upcomming: Option<UpcomingFilter>,
     pub selection: Option<UuidFilter>,
+    /// Calendars visible on first start, others start hidden until toggled
+    pub visible: Option<UuidFilter>,
+    #[serde(default)]
+    pub month_layout: MonthLayout,
+    pub first_weekday: Option<chrono::Weekday>,
+    #[serde(default)]
+    pub week_numbers: bool,
 }
 
-#[derive(Clone, Debug, serde::Deserialize)]
+impl Calendars {
+    #[must_use]
+    pub fn first_weekday(&self) -> chrono::Weekday {
+        self.first_weekday.unwrap_or(chrono::Weekday::Mon)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MonthLayout {
+    /// One colored dot per calendar and day
+    #[default]
+    Dots,
+    /// Spanning bars with titles, multi-day events cross cell borders
+    Bars,
+}
+
+/// Selects calendars, see [`UuidFilter::is_included`] for how the lists combine
+#[derive(Clone, Debug, Default, serde::Deserialize)]
 pub struct UuidFilter {
     #[serde(default)]
-    pub exclude: Vec<Uuid>,
+    pub exclude: Vec<CalendarMatcher>,
     #[serde(default)]
-    pub include: Vec<Uuid>,
+    pub include: Vec<CalendarMatcher>,
+}
+
+/// A calendar uid, or otherwise a display name which may contain `*` and `?` wildcards
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize)]
+#[serde(from = "String")]
+pub enum CalendarMatcher {
+    Uid(Uuid),
+    Name(String),
 }
 
 #[derive(Clone, Debug, serde::Deserialize)]
 pub struct UpcomingFilter {
     #[serde(default)]
-    pub exclude: Vec<Uuid>,
+    pub exclude: Vec<CalendarMatcher>,
+    #[serde(default)]
+    pub include: Vec<CalendarMatcher>,
     #[serde(default)]
-    pub include: Vec<Uuid>,
+    pub skip_oneliner: Vec<CalendarMatcher>,
     #[serde(default)]
-    pub skip_oneliner: Vec<Uuid>,
+    pub sections: Vec<UpcomingSection>,
+}
+
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct UpcomingSection {
+    pub label: String,
+    pub range: UpcomingRange,
+    /// Replaces the filter of the upcoming widget for this section
+    pub filter: Option<UuidFilter>,
+    pub max_items: Option<usize>,
+}
+
+/// Relative date range of an upcoming section
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Deserialize)]
+#[serde(try_from = "String")]
+pub enum UpcomingRange {
+    /// "today", "tomorrow" or "+Nd": a single day
+    Day(u32),
+    /// "next weekend": the upcoming weekend days after tomorrow
+    NextWeekend,
+    /// "rest of week": tomorrow until the end of the week
+    RestOfWeek,
+    /// "next N days": tomorrow and the following days
+    NextDays(u32),
+}
+
+impl TryFrom<String> for UpcomingRange {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let normalized = value.trim().to_ascii_lowercase();
+
+        let range = match normalized.as_str() {
+            "today" => Self::Day(0),
+            "tomorrow" => Self::Day(1),
+            "next weekend" => Self::NextWeekend,
+            "rest of week" => Self::RestOfWeek,
+            range => {
+                if let Some(days) = range.strip_prefix('+').and_then(|r| r.strip_suffix('d')) {
+                    Self::Day(days.parse().map_err(|_| format!("invalid day offset \"{value}\""))?)
+                } else if let Some(days) = range
+                    .strip_prefix("next ")
+                    .and_then(|r| r.strip_suffix(" days"))
+                {
+                    Self::NextDays(days.parse().map_err(|_| format!("invalid day count \"{value}\""))?)
+                } else {
+                    return Err(format!("unknown range \"{value}\""));
+                }
+            }
+        };
+
+        Ok(range)
+    }
 }
 
 impl UuidFilter {
+    /// A calendar matching any `exclude` entry is never included.
+    /// Otherwise it is included if `include` is empty or any of its entries matches.
     #[must_use]
-    pub fn is_included(&self, uid: &Uuid) -> bool {
-        if !self.include.is_empty() && self.include.contains(uid) {
-            return true;
-        }
+    pub fn is_included(&self, uid: &Uuid, name: &str) -> bool {
+        let matches = |matchers: &[CalendarMatcher]| matchers.iter().any(|matcher| matcher.matches(uid, name));
 
-        if !self.exclude.is_empty() && !self.exclude.contains(uid) {
-            return true;
+        !matches(&self.exclude) && (self.include.is_empty() || matches(&self.include))
+    }
+}
+
+impl CalendarMatcher {
+    #[must_use]
+    pub fn matches(&self, uid: &Uuid, name: &str) -> bool {
+        match self {
+            Self::Uid(expected) => expected == uid,
+            Self::Name(pattern) => glob_matches(&pattern.to_lowercase(), &name.to_lowercase()),
         }
+    }
+}
 
-        false
+impl From<String> for CalendarMatcher {
+    fn from(value: String) -> Self {
+        Uuid::parse_str(&value).map_or(Self::Name(value), Self::Uid)
     }
 }
 
+/// Match `*` against any sequence and `?` against any single character.
+fn glob_matches(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    // Position after the last `*` in the pattern and the text position it was tried at
+    let mut backtrack = None;
+    let (mut p, mut t) = (0, 0);
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                p += 1;
+                backtrack = Some((p, t));
+            }
+            Some(c) if *c == '?' || *c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star_p, star_t)) => {
+                    p = star_p;
+                    t = star_t + 1;
+                    backtrack = Some((star_p, star_t + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
 #[derive(Clone, Debug, serde::Deserialize)]
 pub struct Screensaver {
     #[serde(default = "default_screensaver_timeout")]
@@ -95,6 +417,8 @@ pub struct Screensaver {
     pub exclude: Vec<StartEndTimes>,
     #[serde(default)]
     pub dim: Vec<StartEndTimes>,
+    /// Name of a video kept running as picture-in-picture on the screensaver
+    pub pip: Option<String>,
 }
 
 impl Default for Screensaver {
@@ -103,6 +427,7 @@ impl Default for Screensaver {
             timeout: default_screensaver_timeout(),
             exclude: Vec::default(),
             dim: Vec::default(),
+            pip: None,
         }
     }
 }
@@ -137,6 +462,111 @@ pub struct GrafanaPanel {
     pub developer_extras: bool,
 }
 
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct Holidays {
+    /// ISO 3166 country code, e.g. "DE"
+    pub country: String,
+    /// State code for regional holidays, e.g. "BY"
+    pub state: Option<String>,
+    /// Path to an `.ics` file with school vacations
+    pub school_vacations: Option<PathBuf>,
+}
+
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct Countdown {
+    #[serde(default)]
+    pub marks: Vec<CountdownMark>,
+    #[serde(default = "default_countdown_max_items")]
+    pub max_items: usize,
+}
+
+/// Marks events for the countdown, either by their uid or a keyword in their summary
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct CountdownMark {
+    pub uid: Option<Uuid>,
+    pub keyword: Option<String>,
+    /// Shown instead of the event summary
+    pub label: Option<String>,
+}
+
+/// A calendar on the `CalDAV` server only shared with free/busy access
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct FreeBusy {
+    pub url: Url,
+    pub name: String,
+    #[serde(default, deserialize_with = "deserialize_color_opt")]
+    pub color: Option<Color>,
+}
+
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct Conflicts {
+    /// Calendars checked for overlapping events, all if unset
+    #[serde(default)]
+    pub calendars: UuidFilter,
+    /// List conflicts of the next days in the upcoming summary
+    #[serde(default)]
+    pub upcoming: bool,
+    #[serde(default = "default_conflicts_days")]
+    pub days: u32,
+}
+
+#[derive(Clone, Debug, Default, serde::Deserialize)]
+pub struct Colors {
+    /// Replaces the colors of the `CalDAV` calendars, each keeps its color as calendars come and go
+    pub palette: Option<ColorPalette>,
+    /// Assign indicator shapes to the `CalDAV` calendars like the palette colors
+    #[serde(default)]
+    pub shapes: bool,
+    /// Colors and shapes of single calendars, taking precedence over the palette
+    #[serde(default)]
+    pub overrides: Vec<ColorOverride>,
+}
+
+/// Color-blind safe palettes
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ColorPalette {
+    /// Okabe & Ito, without black
+    OkabeIto,
+    /// Paul Tol's bright scheme
+    TolBright,
+}
+
+impl ColorPalette {
+    #[must_use]
+    pub const fn colors(self) -> &'static [Color] {
+        match self {
+            Self::OkabeIto => &[
+                Color::new(230, 159, 0),
+                Color::new(86, 180, 233),
+                Color::new(0, 158, 115),
+                Color::new(240, 228, 66),
+                Color::new(0, 114, 178),
+                Color::new(213, 94, 0),
+                Color::new(204, 121, 167),
+            ],
+            Self::TolBright => &[
+                Color::new(68, 119, 170),
+                Color::new(238, 102, 119),
+                Color::new(34, 136, 51),
+                Color::new(204, 187, 68),
+                Color::new(102, 204, 238),
+                Color::new(170, 51, 119),
+                Color::new(187, 187, 187),
+            ],
+        }
+    }
+}
+
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct ColorOverride {
+    pub calendar: CalendarMatcher,
+    /// Hex color like `#1e66f5`
+    #[serde(default, deserialize_with = "deserialize_color_opt")]
+    pub color: Option<Color>,
+    pub shape: Option<IndicatorShape>,
+}
+
 #[derive(Clone, Debug, serde::Deserialize)]
 pub struct StartEndTimes {
     pub start: chrono::NaiveTime,
@@ -154,6 +584,67 @@ const fn default_screensaver_timeout() -> u16 {
     600
 }
 
+const fn default_tour_enabled() -> bool {
+    true
+}
+
+const fn default_tour_dwell() -> u32 {
+    20
+}
+
+const fn default_tour_pause() -> u32 {
+    120
+}
+
+const fn default_video_popup_duration() -> u64 {
+    30
+}
+
+const fn default_ptz_speed() -> f32 {
+    0.5
+}
+
+const fn default_audio_volume() -> f64 {
+    1.0
+}
+
+const fn default_events_history() -> usize {
+    8
+}
+
+const fn default_events_highlight() -> u32 {
+    30
+}
+
+const fn default_gallery_size() -> usize {
+    12
+}
+
+const fn default_video_grid_columns() -> u16 {
+    2
+}
+
+const fn default_countdown_max_items() -> usize {
+    3
+}
+
+const fn default_conflicts_days() -> u32 {
+    7
+}
+
+fn default_locale() -> String {
+    crate::i18n::DEFAULT_LOCALE.to_owned()
+}
+
+fn deserialize_color_opt<'de, D>(deserializer: D) -> Result<Option<Color>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s = <String as Deserialize>::deserialize(deserializer)?;
+
+    s.parse().map(Some).map_err(de::Error::custom)
+}
+
 fn deserialize_from_file_opt<'de, D>(deserializer: D) -> Result<String, D::Error>
 where
     D: Deserializer<'de>,
@@ -166,3 +657,59 @@ where
 
     Ok(s)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WORK: Uuid = Uuid::from_u128(1);
+    const FAMILY: Uuid = Uuid::from_u128(2);
+
+    fn filter(include: &[&str], exclude: &[&str]) -> UuidFilter {
+        let matchers = |list: &[&str]| list.iter().map(|s| CalendarMatcher::from((*s).to_owned())).collect();
+
+        UuidFilter {
+            include: matchers(include),
+            exclude: matchers(exclude),
+        }
+    }
+
+    #[test]
+    fn empty_filter_includes_everything() {
+        assert!(filter(&[], &[]).is_included(&WORK, "Work"));
+    }
+
+    #[test]
+    fn include_restricts() {
+        let filter = filter(&[&WORK.to_string()], &[]);
+
+        assert!(filter.is_included(&WORK, "Work"));
+        assert!(!filter.is_included(&FAMILY, "Family"));
+    }
+
+    #[test]
+    fn exclude_removes() {
+        let filter = filter(&[], &["family"]);
+
+        assert!(filter.is_included(&WORK, "Work"));
+        assert!(!filter.is_included(&FAMILY, "Family"));
+    }
+
+    #[test]
+    fn exclude_wins_over_include() {
+        let filter = filter(&["*"], &[&FAMILY.to_string()]);
+
+        assert!(filter.is_included(&WORK, "Work"));
+        assert!(!filter.is_included(&FAMILY, "Family"));
+    }
+
+    #[test]
+    fn glob() {
+        assert!(glob_matches("fam*", "family"));
+        assert!(glob_matches("*ly", "family"));
+        assert!(glob_matches("f?m*y", "family"));
+        assert!(glob_matches("*a*i*", "family"));
+        assert!(!glob_matches("fam", "family"));
+        assert!(!glob_matches("?", ""));
+    }
+}
@@ -0,0 +1,337 @@
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::time::Duration;
+
+use chrono::{DateTime, Local};
+use url::Url;
+
+use crate::config;
+use crate::messaging;
+use crate::messaging::VideoMessage;
+
+/// Larger bodies are not motion events
+const MAX_BODY_SIZE: usize = 64 * 1024;
+/// Limits the request line and headers, so a client can not make them grow without end
+const MAX_HEADER_SIZE: u64 = 8 * 1024;
+/// Connections are handled one after another, a stalled client must not block the others
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Motion or object detected by a camera or NVR
+#[derive(Debug, Clone)]
+pub struct MotionEvent {
+    pub camera: String,
+    pub label: String,
+    pub time: DateTime<Local>,
+}
+
+/// Accepts motion events on a webhook endpoint and passes them to the video widget.
+///
+/// Events are sent as query parameters, e.g. `GET /?camera=einfahrt&label=motion` from MotionEye,
+/// or as a JSON body, either `{"camera": "einfahrt", "label": "person"}` or a Frigate event.
+/// `GET /show?stream=Haustür&duration=60&snapshot=true` shows a stream full size instead, e.g.
+/// when the doorbell rings.
+pub fn listen(config: &config::Events) {
+    let listener = match TcpListener::bind(config.listen) {
+        Ok(listener) => listener,
+        Err(err) => {
+            log::error!("Events: failed to listen on {}: {err}", config.listen);
+            return;
+        }
+    };
+
+    log::info!("Events: listening on {}", config.listen);
+
+    let show_duration = Duration::from_secs(config.show_duration);
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            match stream {
+                Ok(stream) => handle(stream, show_duration),
+                Err(err) => log::error!("Events: failed to accept connection: {err}"),
+            }
+        }
+    });
+}
+
+fn handle(mut stream: TcpStream, show_duration: Duration) {
+    if let Err(err) = stream.set_read_timeout(Some(READ_TIMEOUT)) {
+        log::warn!("Events: failed to set read timeout: {err}");
+        return;
+    }
+
+    let status = match read_request(&stream, show_duration) {
+        Ok(Some(message)) => {
+            match &message {
+                VideoMessage::Motion(event) => {
+                    log::info!("Events: {} on \"{}\"", event.label, event.camera);
+                }
+                VideoMessage::ShowStream { name, .. } => {
+                    log::info!("Events: showing stream \"{name}\"");
+                }
+                _ => (),
+            }
+
+            messaging::send_message(message);
+            "204 No Content"
+        }
+        Ok(None) => "204 No Content",
+        Err(err) => {
+            log::warn!("Events: rejected request: {err}");
+            "400 Bad Request"
+        }
+    };
+
+    if let Err(err) = write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+    ) {
+        log::warn!("Events: failed to respond: {err}");
+    }
+}
+
+fn read_request(
+    stream: &TcpStream,
+    show_duration: Duration,
+) -> Result<Option<VideoMessage>, String> {
+    let mut reader = BufReader::new(stream.take(MAX_HEADER_SIZE));
+
+    let mut request_line = String::new();
+    read_header_line(&mut reader, &mut request_line)?;
+
+    let target = request_line
+        .split_whitespace()
+        .nth(1)
+        .ok_or("malformed request line")?
+        .to_string();
+
+    let mut content_length = 0;
+    loop {
+        let mut line = String::new();
+        read_header_line(&mut reader, &mut line)?;
+
+        if line.trim().is_empty() {
+            break;
+        }
+
+        if let Some((name, value)) = line.split_once(':')
+            && name.eq_ignore_ascii_case("content-length")
+        {
+            content_length = value.trim().parse().map_err(|_| "invalid content length")?;
+        }
+    }
+
+    if content_length > MAX_BODY_SIZE {
+        return Err(format!("body of {content_length} bytes is too large"));
+    }
+
+    reader.get_mut().set_limit(MAX_BODY_SIZE as u64);
+
+    let mut body = vec![0; content_length];
+    reader
+        .read_exact(&mut body)
+        .map_err(|err| err.to_string())?;
+
+    parse_request(&target, &body, show_duration)
+}
+
+/// Fails on lines cut off by the end of the connection or the header size limit
+fn read_header_line(reader: &mut impl BufRead, line: &mut String) -> Result<(), String> {
+    reader.read_line(line).map_err(|err| err.to_string())?;
+
+    if line.ends_with('\n') {
+        Ok(())
+    } else {
+        Err(format!(
+            "headers incomplete or larger than {MAX_HEADER_SIZE} bytes"
+        ))
+    }
+}
+
+fn parse_request(
+    target: &str,
+    body: &[u8],
+    show_duration: Duration,
+) -> Result<Option<VideoMessage>, String> {
+    let url = Url::parse("http://localhost")
+        .and_then(|base| base.join(target))
+        .map_err(|err| err.to_string())?;
+
+    if url.path() == "/show" {
+        parse_show(&url, show_duration).map(Some)
+    } else {
+        Ok(parse_event(&url, body)?.map(VideoMessage::Motion))
+    }
+}
+
+fn query(url: &Url, key: &str) -> Option<String> {
+    url.query_pairs()
+        .find(|(name, _)| name == key)
+        .map(|(_, value)| value.into_owned())
+}
+
+/// `stream` is the name of the video, `duration` in seconds and `snapshot` are optional
+fn parse_show(url: &Url, show_duration: Duration) -> Result<VideoMessage, String> {
+    let name = query(url, "stream").ok_or("missing stream")?;
+
+    let duration = match query(url, "duration") {
+        Some(duration) => Duration::from_secs(
+            duration
+                .parse()
+                .map_err(|_| format!("invalid duration \"{duration}\""))?,
+        ),
+        None => show_duration,
+    };
+
+    let snapshot = match query(url, "snapshot").as_deref() {
+        Some("true" | "1") => true,
+        Some("false" | "0") | None => false,
+        Some(snapshot) => return Err(format!("invalid snapshot \"{snapshot}\"")),
+    };
+
+    Ok(VideoMessage::ShowStream {
+        name,
+        duration,
+        snapshot,
+    })
+}
+
+/// Events without a camera, or Frigate updates of events already shown, are ignored
+fn parse_event(url: &Url, body: &[u8]) -> Result<Option<MotionEvent>, String> {
+    let query = |key: &str| query(url, key);
+
+    let (camera, label) = if let Some(camera) = query("camera") {
+        (camera, query("label"))
+    } else if body.is_empty() {
+        return Ok(None);
+    } else {
+        let json: serde_json::Value =
+            serde_json::from_slice(body).map_err(|err| err.to_string())?;
+
+        // Frigate sends the state of the event after the change
+        let event = match json.get("type").and_then(serde_json::Value::as_str) {
+            Some("new") => json.get("after").unwrap_or(&json),
+            Some(_) => return Ok(None),
+            None => &json,
+        };
+
+        let field = |key: &str| {
+            event
+                .get(key)
+                .and_then(serde_json::Value::as_str)
+                .map(str::to_string)
+        };
+
+        let Some(camera) = field("camera") else {
+            return Ok(None);
+        };
+
+        (camera, field("label"))
+    };
+
+    Ok(Some(MotionEvent {
+        camera,
+        label: label.unwrap_or_else(|| "motion".to_string()),
+        time: Local::now(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHOW_DURATION: Duration = Duration::from_secs(30);
+
+    fn parse(target: &str, body: &str) -> Option<(String, String)> {
+        match parse_request(target, body.as_bytes(), SHOW_DURATION).unwrap() {
+            Some(VideoMessage::Motion(event)) => Some((event.camera, event.label)),
+            None => None,
+            Some(message) => panic!("unexpected message {message:?}"),
+        }
+    }
+
+    fn show(target: &str) -> Result<(String, u64, bool), String> {
+        match parse_request(target, b"", SHOW_DURATION)? {
+            Some(VideoMessage::ShowStream {
+                name,
+                duration,
+                snapshot,
+            }) => Ok((name, duration.as_secs(), snapshot)),
+            message => panic!("unexpected message {message:?}"),
+        }
+    }
+
+    fn event(camera: &str, label: &str) -> Option<(String, String)> {
+        Some((camera.to_string(), label.to_string()))
+    }
+
+    #[test]
+    fn query() {
+        assert_eq!(
+            parse("/?camera=einfahrt&label=person", ""),
+            event("einfahrt", "person")
+        );
+        assert_eq!(parse("/?camera=einfahrt", ""), event("einfahrt", "motion"));
+        assert_eq!(parse("/", ""), None);
+    }
+
+    #[test]
+    fn json() {
+        assert_eq!(
+            parse("/", r#"{"camera": "einfahrt", "label": "person"}"#),
+            event("einfahrt", "person")
+        );
+        assert_eq!(
+            parse("/", r#"{"camera": "einfahrt"}"#),
+            event("einfahrt", "motion")
+        );
+        assert_eq!(parse("/", r#"{"label": "person"}"#), None);
+        assert!(parse_request("/", b"{", SHOW_DURATION).is_err());
+    }
+
+    #[test]
+    fn frigate() {
+        let event_of = |kind: &str| {
+            format!(
+                r#"{{"type": "{kind}", "before": {{"camera": "garten", "label": "cat"}}, "after": {{"camera": "einfahrt", "label": "car"}}}}"#
+            )
+        };
+
+        assert_eq!(parse("/", &event_of("new")), event("einfahrt", "car"));
+        assert_eq!(parse("/", &event_of("update")), None);
+        assert_eq!(parse("/", &event_of("end")), None);
+    }
+
+    #[test]
+    fn query_before_body() {
+        assert_eq!(
+            parse("/?camera=tor", r#"{"camera": "einfahrt"}"#),
+            event("tor", "motion")
+        );
+    }
+
+    #[test]
+    fn header_limit() {
+        let mut line = String::new();
+        let mut reader = BufReader::new("GET / HTTP/1.1".as_bytes());
+        assert!(read_header_line(&mut reader, &mut line).is_err());
+
+        let header = format!("X-Padding: {}\r\n", "a".repeat(MAX_HEADER_SIZE as usize));
+        let mut reader = BufReader::new(header.as_bytes().take(MAX_HEADER_SIZE));
+        assert!(read_header_line(&mut reader, &mut line).is_err());
+    }
+
+    #[test]
+    fn show_stream() {
+        assert_eq!(
+            show("/show?stream=Haust%C3%BCr"),
+            Ok(("Haustür".to_string(), 30, false))
+        );
+        assert_eq!(
+            show("/show?stream=tor&duration=60&snapshot=true"),
+            Ok(("tor".to_string(), 60, true))
+        );
+        assert!(show("/show").is_err());
+        assert!(show("/show?stream=tor&duration=soon").is_err());
+        assert!(show("/show?stream=tor&snapshot=yes").is_err());
+    }
+}
@@ -0,0 +1,55 @@
+# Deutsch
+
+page-calendar = Kalender
+page-year = Jahr
+page-grafana = Grafana
+
+video-grid = Alle
+video-tour = Rundgang
+video-offline = Kamera offline
+video-snapshot = Foto
+video-gallery = Galerie
+video-mute = Stumm
+video-unmute = Ton an
+video-talk = Sprechen
+video-events = Ereignisse
+
+event-no-title = <kein Titel>
+
+calendar-holidays = Feiertage
+calendar-school-vacations = Schulferien
+calendar-birthdays = Geburtstage
+free-busy-busy = Belegt
+
+birthday = Geburtstag von { $name }
+birthday-age = { $name } wird { $age }
+anniversary = Jahrestag von { $name }
+anniversary-years = { $name }: { $years }. Jahrestag
+
+upcoming-today = Heute
+upcoming-tomorrow = Morgen
+upcoming-sunday = Sonntag
+upcoming-next-weekend = Nächstes Wochenende
+upcoming-more = +{ $count } weitere
+upcoming-conflicts = Überschneidungen
+upcoming-conflict = { $first } ↔ { $second } ({ $time })
+
+countdown-today = Heute: { $summary }
+countdown-tomorrow = Morgen: { $summary }
+countdown-days =
+    { $days ->
+        [one] Noch { $days } Tag bis { $summary }
+       *[other] Noch { $days } Tage bis { $summary }
+    }
+
+search-placeholder = Termine suchen
+search-no-results = Keine Termine gefunden
+
+format-event-date = %d. %b %H:%M
+format-event-date-year = %d. %b %Y %H:%M
+format-month-year = %B %Y
+format-month = %B
+format-weekday = %a
+format-screensaver-date = %d. %B
+format-search-date = %a %d.%m.%Y
+format-conflict = %a %H:%M
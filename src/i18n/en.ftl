@@ -0,0 +1,59 @@
+# English
+
+page-calendar = Calendar
+page-year = Year
+page-grafana = Grafana
+
+video-grid = All
+video-tour = Tour
+video-offline = Camera offline
+video-snapshot = Snapshot
+video-gallery = Gallery
+video-mute = Mute
+video-unmute = Unmute
+video-talk = Talk
+video-events = Events
+
+event-no-title = <no title>
+
+calendar-holidays = Holidays
+calendar-school-vacations = School vacations
+calendar-birthdays = Birthdays
+free-busy-busy = Busy
+
+birthday = { $name }'s birthday
+birthday-age = { $name } turns { $age }
+anniversary = { $name }'s anniversary
+anniversary-years =
+    { $years ->
+        [one] { $name }: { $years } year anniversary
+       *[other] { $name }: { $years } years anniversary
+    }
+
+upcoming-today = Today
+upcoming-tomorrow = Tomorrow
+upcoming-sunday = Sunday
+upcoming-next-weekend = Next weekend
+upcoming-more = +{ $count } more
+upcoming-conflicts = Conflicts
+upcoming-conflict = { $first } ↔ { $second } ({ $time })
+
+countdown-today = Today: { $summary }
+countdown-tomorrow = Tomorrow: { $summary }
+countdown-days =
+    { $days ->
+        [one] { $days } day until { $summary }
+       *[other] { $days } days until { $summary }
+    }
+
+search-placeholder = Search events
+search-no-results = No events found
+
+format-event-date = %b %d %H:%M
+format-event-date-year = %b %d %Y %H:%M
+format-month-year = %B %Y
+format-month = %B
+format-weekday = %a
+format-screensaver-date = %B %d
+format-search-date = %a %Y-%m-%d
+format-conflict = %a %H:%M
@@ -0,0 +1,169 @@
+use std::sync::OnceLock;
+
+use chrono::{Days, NaiveDate};
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource};
+use unic_langid::LanguageIdentifier;
+
+pub use fluent_bundle::FluentValue;
+
+/// Fluent translation catalogs by language
+static CATALOGS: &[(&str, &str)] = &[
+    ("de", include_str!("de.ftl")),
+    ("en", include_str!("en.ftl")),
+];
+
+static FALLBACK_LANGUAGE: &str = "en";
+pub static DEFAULT_LOCALE: &str = "de_DE";
+
+static I18N: OnceLock<I18n> = OnceLock::new();
+
+type Bundle = FluentBundle<FluentResource>;
+
+struct I18n {
+    locale: chrono::Locale,
+    bundle: Option<Bundle>,
+    fallback: Option<Bundle>,
+}
+
+impl I18n {
+    fn new(locale_str: &str) -> Self {
+        let locale = chrono::Locale::try_from(locale_str).unwrap_or_else(|_| {
+            log::warn!("I18n: unknown locale \"{locale_str}\", using \"{DEFAULT_LOCALE}\"");
+            chrono::Locale::try_from(DEFAULT_LOCALE).expect("Default locale is valid")
+        });
+
+        let language = locale_str.split(['_', '-']).next().unwrap_or_default();
+
+        let bundle = load_bundle(language).or_else(|| {
+            log::warn!("I18n: no translations for \"{language}\", using \"{FALLBACK_LANGUAGE}\"");
+            load_bundle(FALLBACK_LANGUAGE)
+        });
+
+        Self {
+            locale,
+            bundle,
+            fallback: load_bundle(FALLBACK_LANGUAGE),
+        }
+    }
+}
+
+/// Select the locale used for translations and date formatting.
+/// Must be called before the first translation is requested, later calls are ignored.
+pub fn init(locale: &str) {
+    if I18N.set(I18n::new(locale)).is_err() {
+        log::warn!("I18n: already initialized, ignoring locale \"{locale}\"");
+    }
+}
+
+fn get() -> &'static I18n {
+    I18N.get_or_init(|| I18n::new(DEFAULT_LOCALE))
+}
+
+/// The configured locale for [`chrono::format::Locale`] aware formatting.
+pub fn locale() -> chrono::Locale {
+    get().locale
+}
+
+/// Translate a message by its key.
+/// Falls back to the english catalog and finally to the key itself.
+pub fn tr(key: &str) -> String {
+    tr_args(key, &[])
+}
+
+/// Translate a message by its key with arguments for its `{ $name }` placeables.
+/// Numbers select the plural variant, e.g. `{ $count -> [one] ... *[other] ... }`.
+pub fn tr_args(key: &str, args: &[(&str, FluentValue<'_>)]) -> String {
+    let i18n = get();
+
+    let args: FluentArgs = args.iter().cloned().collect();
+
+    [&i18n.bundle, &i18n.fallback]
+        .into_iter()
+        .flatten()
+        .find_map(|bundle| format(bundle, key, &args))
+        .unwrap_or_else(|| {
+            log::warn!("I18n: missing translation for \"{key}\"");
+            key.to_owned()
+        })
+}
+
+/// Abbreviated weekday names in the configured locale, starting with the given weekday.
+pub fn weekday_labels(first: chrono::Weekday) -> [String; 7] {
+    // 2024-01-01 was a monday
+    let monday = NaiveDate::from_ymd_opt(2024, 1, 1).expect("Valid date");
+    let start = monday + Days::new(u64::from(first.num_days_from_monday()));
+    let format = tr("format-weekday");
+
+    std::array::from_fn(|idx| {
+        let date = start + Days::new(idx as u64);
+
+        date.format_localized(&format, locale()).to_string()
+    })
+}
+
+fn load_bundle(language: &str) -> Option<Bundle> {
+    let (_, source) = CATALOGS.iter().find(|(lang, _)| *lang == language)?;
+    let language: LanguageIdentifier = language.parse().ok()?;
+
+    let resource =
+        FluentResource::try_new((*source).to_owned()).unwrap_or_else(|(resource, errors)| {
+            log::error!("I18n: invalid catalog \"{language}\": {errors:?}");
+            resource
+        });
+
+    let mut bundle = FluentBundle::new_concurrent(vec![language]);
+    // GTK lays out the text itself, Unicode isolation marks would show up in labels
+    bundle.set_use_isolating(false);
+    bundle.add_resource_overriding(resource);
+
+    Some(bundle)
+}
+
+fn format(bundle: &Bundle, key: &str, args: &FluentArgs) -> Option<String> {
+    let pattern = bundle.get_message(key)?.value()?;
+    let mut errors = Vec::new();
+    let message = bundle.format_pattern(pattern, Some(args), &mut errors);
+
+    if !errors.is_empty() {
+        log::warn!("I18n: failed to format \"{key}\": {errors:?}");
+    }
+
+    Some(message.into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn translate(language: &str, key: &str, args: &[(&str, FluentValue<'_>)]) -> String {
+        let bundle = load_bundle(language).unwrap();
+        let args: FluentArgs = args.iter().cloned().collect();
+
+        format(&bundle, key, &args).unwrap()
+    }
+
+    #[test]
+    fn plurals() {
+        let days = |language, days: i64| {
+            translate(
+                language,
+                "countdown-days",
+                &[("days", days.into()), ("summary", "Urlaub".into())],
+            )
+        };
+
+        assert_eq!(days("de", 1), "Noch 1 Tag bis Urlaub");
+        assert_eq!(days("de", 5), "Noch 5 Tage bis Urlaub");
+        assert_eq!(days("en", 1), "1 day until Urlaub");
+        assert_eq!(days("en", 5), "5 days until Urlaub");
+    }
+
+    #[test]
+    fn placeables_are_not_isolated() {
+        assert_eq!(
+            translate("en", "birthday", &[("name", "Anna".into())]),
+            "Anna's birthday"
+        );
+    }
+}
@@ -16,19 +16,24 @@ use crate::prelude::*;
 mod app;
 pub mod calendar;
 pub mod config;
+pub mod events;
 pub mod gtk_ext;
+pub mod i18n;
 pub mod messaging;
+pub mod onvif;
 pub mod prelude;
+pub mod state;
 pub mod widgets;
 
 fn main() {
     env_logger::builder().init();
     clapper::init().unwrap();
+    widgets::video::source::register();
 
     let mut args = std::env::args().collect::<Vec<_>>();
 
     if args.len() < 2 {
-        eprintln!("Usage: {} <config> <other-args>", args[0]);
+        eprintln!("Usage: {} <config> [--list-calendars] <other-args>", args[0]);
         std::process::exit(1);
     }
 
@@ -41,6 +46,12 @@ fn main() {
         }
     };
 
+    i18n::init(&config.locale);
+
+    if args.iter().any(|arg| arg == "--list-calendars") {
+        list_calendars(&config);
+    }
+
     let app = Application::builder()
         .application_id("de.cyl3x.home-control-panel")
         .build();
@@ -59,6 +70,10 @@ fn main() {
     app.connect_activate(move |app| {
         let mut app = App::new(app, &config);
 
+        if let Some(events) = &config.events {
+            events::listen(events);
+        }
+
         glib::spawn_future_local(async move {
             while let Ok(message) = messaging::receiver().recv().await {
                 app.update(message);
@@ -72,12 +87,45 @@ fn main() {
 
             glib::ControlFlow::Continue
         });
+
+        if let Some(popup) = config.video_popup.clone() {
+            glib::unix_signal_add_local(12 /* USR2 */, move || {
+                log::info!("Received USR2 signal: showing stream \"{}\"", popup.name);
+
+                messaging::send_message(messaging::VideoMessage::ShowStream {
+                    name: popup.name.clone(),
+                    duration: std::time::Duration::from_secs(popup.duration),
+                    snapshot: popup.snapshot,
+                });
+
+                glib::ControlFlow::Continue
+            });
+        }
     });
 
     let exit_code = app.run_with_args(&args);
     std::process::exit(exit_code.into());
 }
 
+/// Print uid, kind and name of all calendars for writing filters, then exit
+fn list_calendars(config: &config::Config) -> ! {
+    let manager = calendar::Manager::new(config);
+
+    match manager.sources.fetch() {
+        Ok(map) => {
+            for (uid, (_, calendar)) in map.calendars() {
+                println!("{uid}  {:<16} {}", format!("{:?}", calendar.kind), calendar.name);
+            }
+
+            std::process::exit(0);
+        }
+        Err(err) => {
+            eprintln!("Failed to fetch calendars: {err:?}");
+            std::process::exit(1);
+        }
+    }
+}
+
 pub fn remove_source(id: Option<glib::SourceId>) {
     if let Some(id) = id
         && glib::MainContext::default()
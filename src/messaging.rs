@@ -34,11 +34,22 @@ pub enum CalendarMessage {
     Fetch,
     MonthPrev,
     MonthNext,
+    YearPrev,
+    YearNext,
+    // The year page became visible, it is not updated while hidden
+    YearShown,
     SelectNow,
     SelectDate(chrono::NaiveDate),
+    // Move the selected day or week by its own length
+    SelectionPrev,
+    SelectionNext,
+    // Move the selection by the given days, keeping a selected week
+    MoveSelection(i64),
     SelectGridIndex(usize),
+    SelectGridWeek(usize),
     UpdateMap(std::boxed::Box<CalendarMap>),
     ToggleCalendar(uuid::Uuid),
+    Search(String),
 }
 
 impl From<CalendarMessage> for AppMessage {
@@ -51,6 +62,38 @@ impl From<CalendarMessage> for AppMessage {
 pub enum VideoMessage {
     CheckVideoState(Option<clapper::PlayerState>),
     VideoSelectIndex(Option<usize>),
+    ShowGrid,
+    // Show the next stream of the tour after the dwell time of the current one
+    TourNext,
+    ToggleTour,
+    ShowGallery,
+    ToggleMute,
+    // Push-to-talk to the shown stream, true while the talk button is held
+    Talk(bool),
+    PtzConnected(usize, Option<Box<crate::onvif::Ptz>>),
+    // Move the PTZ camera of the shown stream while a control is held, None stops it
+    PtzMove(Option<crate::onvif::Direction>),
+    PtzPreset(String),
+    Motion(crate::events::MotionEvent),
+    // The highlight of a stream after a motion event timed out
+    MotionExpired(usize),
+    // Save the current frame of the shown stream
+    Snapshot,
+    // Wake up and show a stream full size for a while, e.g. when the doorbell rings
+    ShowStream {
+        name: String,
+        duration: std::time::Duration,
+        // Save a snapshot once the stream delivers frames
+        snapshot: bool,
+    },
+    // The stream of `ShowStream` is selected, the app gives it the whole window for the duration
+    StreamShown {
+        duration: std::time::Duration,
+        // Return to the grid afterwards
+        grid: bool,
+    },
+    // Stop decoding streams while the screensaver covers them
+    ScreensaverActive(bool),
 }
 
 impl From<VideoMessage> for AppMessage {
@@ -0,0 +1,634 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime};
+
+use base64::prelude::*;
+use ureq::Agent;
+use ureq::config::Config;
+use ureq::http;
+use url::Url;
+
+use crate::config;
+
+const NS_DEVICE: &str = "http://www.onvif.org/ver10/device/wsdl";
+const NS_MEDIA: &str = "http://www.onvif.org/ver10/media/wsdl";
+const NS_PTZ: &str = "http://www.onvif.org/ver20/ptz/wsdl";
+const NS_SCHEMA: &str = "http://www.onvif.org/ver10/schema";
+
+/// Cameras stop moving on their own after this, in case the stop request gets lost
+const MOVE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Minimal ONVIF client speaking SOAP 1.2 with WS-Security `UsernameToken` digest authentication.
+#[derive(Clone)]
+pub struct Client {
+    agent: Agent,
+    device_url: Url,
+    username: String,
+    password: String,
+}
+
+impl core::fmt::Debug for Client {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Client")
+            .field("device_url", &self.device_url.as_str())
+            .finish_non_exhaustive()
+    }
+}
+
+/// PTZ service of a single media profile, discovered through the device service
+#[derive(Debug, Clone)]
+pub struct Ptz {
+    client: Client,
+    url: String,
+    profile: String,
+    presets: Vec<Preset>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Preset {
+    pub token: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+    ZoomIn,
+    ZoomOut,
+}
+
+impl Direction {
+    /// Pan, tilt and zoom velocity in the generic ONVIF space from -1.0 to 1.0
+    const fn velocity(self, speed: f32) -> (f32, f32, f32) {
+        match self {
+            Self::Up => (0.0, speed, 0.0),
+            Self::Down => (0.0, -speed, 0.0),
+            Self::Left => (-speed, 0.0, 0.0),
+            Self::Right => (speed, 0.0, 0.0),
+            Self::ZoomIn => (0.0, 0.0, speed),
+            Self::ZoomOut => (0.0, 0.0, -speed),
+        }
+    }
+}
+
+impl From<&config::Onvif> for Client {
+    fn from(onvif: &config::Onvif) -> Self {
+        Self::new(
+            onvif.url.clone(),
+            onvif.username.clone(),
+            onvif.password.clone(),
+        )
+    }
+}
+
+impl Client {
+    pub fn new(device_url: Url, username: String, password: String) -> Self {
+        Self {
+            agent: Config::builder()
+                .http_status_as_error(false)
+                .timeout_global(Some(Duration::from_secs(5)))
+                .build()
+                .new_agent(),
+            device_url,
+            username,
+            password,
+        }
+    }
+
+    /// Look up the PTZ service and presets of the given profile, identified by token or name,
+    /// or of the first profile with a PTZ configuration.
+    ///
+    /// # Errors
+    /// Returns an error if a request fails or the device does not support PTZ.
+    pub fn ptz(&self, profile: Option<&str>) -> Result<Ptz, Error> {
+        let capabilities = self.call(
+            self.device_url.as_str(),
+            &format!(
+                r#"<GetCapabilities xmlns="{NS_DEVICE}"><Category>All</Category></GetCapabilities>"#
+            ),
+        )?;
+
+        let media_url = service_url(&capabilities, "Media")?;
+        let url = service_url(&capabilities, "PTZ")?;
+
+        let profiles = self.call(&media_url, &format!(r#"<GetProfiles xmlns="{NS_MEDIA}"/>"#))?;
+
+        let profile = children(&profiles, "Profiles")
+            .into_iter()
+            .find(|element| {
+                profile.map_or_else(
+                    || find(element, "PTZConfiguration").is_some(),
+                    |profile| {
+                        element.attributes.get("token").map(String::as_str) == Some(profile)
+                            || child_text(element, "Name").as_deref() == Some(profile)
+                    },
+                )
+            })
+            .and_then(|element| element.attributes.get("token").cloned())
+            .ok_or_else(|| Error {
+                kind: ErrorKind::Parsing,
+                message: format!("No PTZ profile found, requested: {profile:?}"),
+            })?;
+
+        let mut ptz = Ptz {
+            client: self.clone(),
+            url,
+            profile,
+            presets: Vec::new(),
+        };
+
+        ptz.presets = ptz.get_presets()?;
+
+        Ok(ptz)
+    }
+
+    fn call(&self, url: &str, body: &str) -> Result<xmltree::Element, Error> {
+        let request = http::Request::builder()
+            .method("POST")
+            .uri(url)
+            .header("Content-Type", "application/soap+xml; charset=utf-8")
+            .body(self.envelope(body))
+            .map_err(|e| Error {
+                kind: ErrorKind::Parsing,
+                message: e.to_string(),
+            })?;
+
+        let response = self.agent.run(request)?;
+        let status = response.status();
+        let root = xmltree::Element::parse(response.into_body().as_reader());
+
+        // SOAP faults come with an error status, but their reason is more helpful
+        if let Ok(root) = &root
+            && let Some(fault) = find(root, "Fault")
+        {
+            return Err(Error {
+                kind: ErrorKind::Soap,
+                message: find(fault, "Reason")
+                    .and_then(|reason| child_text(reason, "Text"))
+                    .unwrap_or_else(|| status.to_string()),
+            });
+        }
+
+        if !status.is_success() {
+            return Err(Error {
+                kind: ErrorKind::Http,
+                message: format!("HTTP error calling \"{url}\": {status}"),
+            });
+        }
+
+        let root = root?;
+
+        find(&root, "Body").cloned().ok_or_else(|| Error {
+            kind: ErrorKind::Parsing,
+            message: format!("Missing SOAP body in response of \"{url}\""),
+        })
+    }
+
+    fn envelope(&self, body: &str) -> String {
+        let nonce = nonce();
+        let created = chrono::Utc::now()
+            .format("%Y-%m-%dT%H:%M:%S%.3fZ")
+            .to_string();
+
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<s:Envelope xmlns:s="http://www.w3.org/2003/05/soap-envelope">
+    <s:Header>
+        <Security s:mustUnderstand="1" xmlns="http://docs.oasis-open.org/wss/2004/01/oasis-200401-wss-wssecurity-secext-1.0.xsd">
+            <UsernameToken>
+                <Username>{username}</Username>
+                <Password Type="http://docs.oasis-open.org/wss/2004/01/oasis-200401-wss-username-token-profile-1.0#PasswordDigest">{digest}</Password>
+                <Nonce EncodingType="http://docs.oasis-open.org/wss/2004/01/oasis-200401-wss-soap-message-security-1.0#Base64Binary">{nonce}</Nonce>
+                <Created xmlns="http://docs.oasis-open.org/wss/2004/01/oasis-200401-wss-wssecurity-utility-1.0.xsd">{created}</Created>
+            </UsernameToken>
+        </Security>
+    </s:Header>
+    <s:Body>{body}</s:Body>
+</s:Envelope>"#,
+            username = escape(&self.username),
+            digest = password_digest(&nonce, &created, &self.password),
+            nonce = BASE64_STANDARD.encode(nonce),
+        )
+    }
+}
+
+impl Ptz {
+    pub fn presets(&self) -> &[Preset] {
+        &self.presets
+    }
+
+    /// Move until [`Ptz::stop`] is called
+    ///
+    /// # Errors
+    /// Returns an error if the request fails.
+    pub fn continuous_move(&self, direction: Direction, speed: f32) -> Result<(), Error> {
+        let (pan, tilt, zoom) = direction.velocity(speed);
+
+        self.client.call(
+            &self.url,
+            &format!(
+                r#"<ContinuousMove xmlns="{NS_PTZ}"><ProfileToken>{profile}</ProfileToken><Velocity><PanTilt xmlns="{NS_SCHEMA}" x="{pan}" y="{tilt}"/><Zoom xmlns="{NS_SCHEMA}" x="{zoom}"/></Velocity><Timeout>PT{timeout}S</Timeout></ContinuousMove>"#,
+                profile = escape(&self.profile),
+                timeout = MOVE_TIMEOUT.as_secs(),
+            ),
+        )?;
+
+        Ok(())
+    }
+
+    /// # Errors
+    /// Returns an error if the request fails.
+    pub fn stop(&self) -> Result<(), Error> {
+        self.client.call(
+            &self.url,
+            &format!(
+                r#"<Stop xmlns="{NS_PTZ}"><ProfileToken>{profile}</ProfileToken><PanTilt>true</PanTilt><Zoom>true</Zoom></Stop>"#,
+                profile = escape(&self.profile),
+            ),
+        )?;
+
+        Ok(())
+    }
+
+    /// # Errors
+    /// Returns an error if the request fails.
+    pub fn goto_preset(&self, token: &str) -> Result<(), Error> {
+        self.client.call(
+            &self.url,
+            &format!(
+                r#"<GotoPreset xmlns="{NS_PTZ}"><ProfileToken>{profile}</ProfileToken><PresetToken>{token}</PresetToken></GotoPreset>"#,
+                profile = escape(&self.profile),
+                token = escape(token),
+            ),
+        )?;
+
+        Ok(())
+    }
+
+    fn get_presets(&self) -> Result<Vec<Preset>, Error> {
+        let body = self.client.call(
+            &self.url,
+            &format!(
+                r#"<GetPresets xmlns="{NS_PTZ}"><ProfileToken>{profile}</ProfileToken></GetPresets>"#,
+                profile = escape(&self.profile),
+            ),
+        )?;
+
+        Ok(children(&body, "Preset")
+            .into_iter()
+            .filter_map(|element| {
+                let token = element.attributes.get("token")?.clone();
+                let name = child_text(element, "Name").unwrap_or_else(|| token.clone());
+
+                Some(Preset { token, name })
+            })
+            .collect())
+    }
+}
+
+/// `Base64(SHA-1(nonce + created + password))` as defined by the WS-Security username token profile
+fn password_digest(nonce: &[u8], created: &str, password: &str) -> String {
+    let mut sha1 = sha1_smol::Sha1::new();
+    sha1.update(nonce);
+    sha1.update(created.as_bytes());
+    sha1.update(password.as_bytes());
+
+    BASE64_STANDARD.encode(sha1.digest().bytes())
+}
+
+/// Unique per request, which is all the digest needs to prevent replays
+fn nonce() -> [u8; 16] {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    let mut sha1 = sha1_smol::Sha1::new();
+    sha1.update(format!("{:?}", SystemTime::now()).as_bytes());
+    sha1.update(&COUNTER.fetch_add(1, Ordering::Relaxed).to_le_bytes());
+    sha1.update(&std::process::id().to_le_bytes());
+
+    let mut nonce = [0; 16];
+    nonce.copy_from_slice(&sha1.digest().bytes()[..16]);
+    nonce
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn service_url(capabilities: &xmltree::Element, service: &str) -> Result<String, Error> {
+    find(capabilities, service)
+        .and_then(|element| child_text(element, "XAddr"))
+        .ok_or_else(|| Error {
+            kind: ErrorKind::Parsing,
+            message: format!("Device does not offer the {service} service"),
+        })
+}
+
+/// Depth-first search for an element by its local name
+fn find<'a>(element: &'a xmltree::Element, name: &str) -> Option<&'a xmltree::Element> {
+    element
+        .children
+        .iter()
+        .filter_map(xmltree::XMLNode::as_element)
+        .find_map(|child| {
+            if child.name == name {
+                Some(child)
+            } else {
+                find(child, name)
+            }
+        })
+}
+
+/// All elements with the given local name, without descending into matches
+fn children<'a>(element: &'a xmltree::Element, name: &str) -> Vec<&'a xmltree::Element> {
+    let mut found = Vec::new();
+
+    for child in element
+        .children
+        .iter()
+        .filter_map(xmltree::XMLNode::as_element)
+    {
+        if child.name == name {
+            found.push(child);
+        } else {
+            found.extend(children(child, name));
+        }
+    }
+
+    found
+}
+
+fn child_text(element: &xmltree::Element, name: &str) -> Option<String> {
+    element
+        .get_child(name)
+        .and_then(xmltree::Element::get_text)
+        .map(|text| text.trim().to_string())
+}
+
+/// Errors that may occur during ONVIF operations.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Error {
+    pub kind: ErrorKind,
+    pub message: String,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum ErrorKind {
+    Http,
+    Soap,
+    Parsing,
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}: {}", self.kind, self.message)
+    }
+}
+
+impl From<ureq::Error> for Error {
+    fn from(e: ureq::Error) -> Self {
+        Self {
+            kind: ErrorKind::Http,
+            message: format!("{e:?}"),
+        }
+    }
+}
+
+impl From<xmltree::ParseError> for Error {
+    fn from(e: xmltree::ParseError) -> Self {
+        Self {
+            kind: ErrorKind::Parsing,
+            message: e.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+
+    /// Local SOAP server answering with the response of the first operation found in the request.
+    /// `$BASE` in responses is replaced with the address of the server.
+    struct MockServer {
+        url: Url,
+        requests: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl MockServer {
+        fn start(responses: Vec<(&'static str, u16, String)>) -> Self {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let base = format!("http://{}/", listener.local_addr().unwrap());
+            let url = Url::parse(&base)
+                .unwrap()
+                .join("onvif/device_service")
+                .unwrap();
+
+            let requests = Arc::new(Mutex::new(Vec::new()));
+            let received = Arc::clone(&requests);
+
+            std::thread::spawn(move || {
+                for stream in listener.incoming() {
+                    let mut stream = stream.unwrap();
+                    let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+                    let mut content_length = 0;
+                    loop {
+                        let mut line = String::new();
+                        reader.read_line(&mut line).unwrap();
+
+                        if let Some((name, value)) = line.split_once(':')
+                            && name.eq_ignore_ascii_case("content-length")
+                        {
+                            content_length = value.trim().parse().unwrap();
+                        }
+
+                        if line.trim().is_empty() {
+                            break;
+                        }
+                    }
+
+                    let mut body = vec![0; content_length];
+                    reader.read_exact(&mut body).unwrap();
+                    let body = String::from_utf8(body).unwrap();
+
+                    let (status, response) = responses
+                        .iter()
+                        .find(|(operation, _, _)| body.contains(&format!("<{operation} ")))
+                        .map_or((404, String::new()), |(_, status, response)| {
+                            (*status, envelope(&response.replace("$BASE", &base)))
+                        });
+
+                    received.lock().unwrap().push(body);
+
+                    write!(
+                        stream,
+                        "HTTP/1.1 {status} Mock\r\nContent-Type: application/soap+xml\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{response}",
+                        response.len()
+                    )
+                    .unwrap();
+                }
+            });
+
+            Self { url, requests }
+        }
+
+        fn client(&self) -> Client {
+            Client::new(self.url.clone(), "admin".into(), "secret".into())
+        }
+
+        fn requests(&self) -> Vec<String> {
+            self.requests.lock().unwrap().clone()
+        }
+    }
+
+    fn envelope(body: &str) -> String {
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?><s:Envelope xmlns:s="http://www.w3.org/2003/05/soap-envelope" xmlns:tt="{NS_SCHEMA}"><s:Body>{body}</s:Body></s:Envelope>"#
+        )
+    }
+
+    fn camera() -> MockServer {
+        MockServer::start(vec![
+            (
+                "GetCapabilities",
+                200,
+                format!(
+                    r#"<tds:GetCapabilitiesResponse xmlns:tds="{NS_DEVICE}"><tds:Capabilities><tt:Media><tt:XAddr>$BASEmedia</tt:XAddr></tt:Media><tt:PTZ><tt:XAddr>$BASEptz</tt:XAddr></tt:PTZ></tds:Capabilities></tds:GetCapabilitiesResponse>"#
+                ),
+            ),
+            (
+                "GetProfiles",
+                200,
+                format!(
+                    r#"<trt:GetProfilesResponse xmlns:trt="{NS_MEDIA}"><trt:Profiles token="main"><tt:Name>Main</tt:Name></trt:Profiles><trt:Profiles token="ptz"><tt:Name>Sub</tt:Name><tt:PTZConfiguration token="cfg"/></trt:Profiles></trt:GetProfilesResponse>"#
+                ),
+            ),
+            (
+                "GetPresets",
+                200,
+                format!(
+                    r#"<tptz:GetPresetsResponse xmlns:tptz="{NS_PTZ}"><tptz:Preset token="1"><tt:Name>Door</tt:Name></tptz:Preset><tptz:Preset token="2"/></tptz:GetPresetsResponse>"#
+                ),
+            ),
+            (
+                "ContinuousMove",
+                200,
+                format!(r#"<tptz:ContinuousMoveResponse xmlns:tptz="{NS_PTZ}"/>"#),
+            ),
+            (
+                "GotoPreset",
+                500,
+                r#"<s:Fault><s:Reason><s:Text xml:lang="en">Preset not found</s:Text></s:Reason></s:Fault>"#
+                    .to_string(),
+            ),
+        ])
+    }
+
+    #[test]
+    fn password_digest_matches_ws_security() {
+        assert_eq!(
+            password_digest(b"0123456789abcdef", "2026-01-01T00:00:00.000Z", "secret"),
+            "Jcd++c9gZ/c4zFSpJtkPJ3kxLec="
+        );
+    }
+
+    #[test]
+    fn discovers_ptz_profile_and_presets() {
+        let camera = camera();
+        let ptz = camera.client().ptz(None).unwrap();
+
+        assert_eq!(ptz.url, format!("{}ptz", camera.url.join("/").unwrap()));
+        assert_eq!(ptz.profile, "ptz");
+        assert_eq!(
+            ptz.presets(),
+            [
+                Preset {
+                    token: "1".into(),
+                    name: "Door".into(),
+                },
+                Preset {
+                    token: "2".into(),
+                    name: "2".into(),
+                },
+            ]
+        );
+
+        let requests = camera.requests();
+        assert_eq!(requests.len(), 3);
+        assert!(requests[0].contains("<Username>admin</Username>"));
+        assert!(!requests[0].contains("secret"));
+        assert!(requests[2].contains("<ProfileToken>ptz</ProfileToken>"));
+    }
+
+    #[test]
+    fn selects_profile_by_name() {
+        let camera = camera();
+        let ptz = camera.client().ptz(Some("Main")).unwrap();
+
+        assert_eq!(ptz.profile, "main");
+    }
+
+    #[test]
+    fn sends_velocity() {
+        let camera = camera();
+        let ptz = camera.client().ptz(None).unwrap();
+
+        ptz.continuous_move(Direction::Left, 0.5).unwrap();
+
+        let requests = camera.requests();
+        assert!(requests[3].contains(
+            r#"x="-0.5" y="0"/><Zoom xmlns="http://www.onvif.org/ver10/schema" x="0"/>"#
+        ));
+        assert!(requests[3].contains("<Timeout>PT10S</Timeout>"));
+    }
+
+    #[test]
+    fn reports_soap_faults() {
+        let camera = camera();
+        let ptz = camera.client().ptz(None).unwrap();
+
+        assert_eq!(
+            ptz.goto_preset("3"),
+            Err(Error {
+                kind: ErrorKind::Soap,
+                message: "Preset not found".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn reports_missing_ptz_service() {
+        let camera = MockServer::start(vec![(
+            "GetCapabilities",
+            200,
+            format!(
+                r#"<tds:GetCapabilitiesResponse xmlns:tds="{NS_DEVICE}"><tds:Capabilities><tt:Media><tt:XAddr>$BASEmedia</tt:XAddr></tt:Media></tds:Capabilities></tds:GetCapabilitiesResponse>"#
+            ),
+        )]);
+
+        assert_eq!(
+            camera.client().ptz(None).unwrap_err(),
+            Error {
+                kind: ErrorKind::Parsing,
+                message: "Device does not offer the PTZ service".to_string(),
+            }
+        );
+        assert_eq!(camera.requests().len(), 1);
+    }
+
+    #[test]
+    fn reports_http_errors() {
+        let camera = MockServer::start(Vec::new());
+
+        assert_eq!(camera.client().ptz(None).unwrap_err().kind, ErrorKind::Http);
+    }
+}
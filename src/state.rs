@@ -0,0 +1,100 @@
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use uuid::Uuid;
+
+/// Runtime state which survives restarts, stored as TOML next to other user data
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
+pub struct State {
+    /// Visibility of calendars toggled by the user, overriding the configured default
+    #[serde(default)]
+    pub calendar_visibility: BTreeMap<Uuid, bool>,
+}
+
+impl State {
+    /// Load the state from the given path, falling back to an empty state.
+    pub fn load(path: &Path) -> Self {
+        let string = match std::fs::read_to_string(path) {
+            Ok(string) => string,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Self::default(),
+            Err(err) => {
+                log::error!("State: failed to read {}: {err}", path.display());
+                return Self::default();
+            }
+        };
+
+        toml::from_str(&string).unwrap_or_else(|err| {
+            log::error!("State: failed to parse {}: {err}", path.display());
+            Self::default()
+        })
+    }
+
+    /// Write the state to the given path, creating missing parent directories.
+    /// It is written to a temporary file first, so a crash never leaves a truncated state.
+    pub fn save(&self, path: &Path) {
+        let result = toml::to_string(self)
+            .map_err(|err| err.to_string())
+            .and_then(|string| write_atomic(path, &string).map_err(|err| err.to_string()));
+
+        if let Err(err) = result {
+            log::error!("State: failed to write {}: {err}", path.display());
+        }
+    }
+}
+
+fn write_atomic(path: &Path, contents: &str) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let mut temp_name = path.file_name().unwrap_or_default().to_os_string();
+    temp_name.push(".tmp");
+    let temp_path = path.with_file_name(temp_name);
+
+    let mut file = File::create(&temp_path)?;
+    file.write_all(contents.as_bytes())?;
+    file.sync_all()?;
+
+    std::fs::rename(&temp_path, path)
+}
+
+/// `$XDG_STATE_HOME/home-control-panel/state.toml`, `XDG_STATE_HOME` defaults to `~/.local/state`
+pub fn default_path() -> Option<PathBuf> {
+    std::env::var_os("XDG_STATE_HOME")
+        .map(PathBuf::from)
+        // Relative paths are invalid and ignored by the specification
+        .filter(|path| path.is_absolute())
+        .or_else(|| std::env::home_dir().map(|home| home.join(".local/state")))
+        .map(|state| state.join("home-control-panel/state.toml"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let dir = std::env::temp_dir().join(format!("hcp-state-{}", std::process::id()));
+        let path = dir.join("nested/state.toml");
+
+        assert!(State::load(&path).calendar_visibility.is_empty());
+
+        let state = State {
+            calendar_visibility: BTreeMap::from([
+                (Uuid::from_u128(1), true),
+                (Uuid::from_u128(2), false),
+            ]),
+        };
+        state.save(&path);
+
+        assert_eq!(
+            State::load(&path).calendar_visibility,
+            state.calendar_visibility
+        );
+        assert!(!dir.join("nested/state.toml.tmp").exists());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
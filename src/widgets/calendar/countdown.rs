@@ -0,0 +1,99 @@
+use std::collections::HashSet;
+
+use crate::calendar::caldav::TimeRangeFilter;
+use crate::calendar::{Event, Manager};
+use crate::config::{Config, CountdownMark};
+use crate::i18n::tr_args;
+use crate::prelude::*;
+use crate::widgets::calendar::Dates;
+
+pub struct CountdownWidget {
+    marks: Vec<CountdownMark>,
+    max_items: usize,
+    wrapper: gtk::Box,
+    labels: Vec<gtk::Label>,
+}
+
+impl CountdownWidget {
+    pub fn new(config: &Config) -> Self {
+        let wrapper = gtk::Box::new(gtk::Orientation::Vertical, 4);
+        wrapper.add_css_class("calendar-countdown");
+        wrapper.set_visible(false);
+
+        Self {
+            marks: config
+                .countdown
+                .as_ref()
+                .map(|countdown| countdown.marks.clone())
+                .unwrap_or_default(),
+            max_items: config
+                .countdown
+                .as_ref()
+                .map_or(usize::MAX, |countdown| countdown.max_items),
+            wrapper,
+            labels: Vec::new(),
+        }
+    }
+
+    pub const fn widget(&self) -> &gtk::Box {
+        &self.wrapper
+    }
+
+    pub fn update_calendar(&mut self, manager: &Manager, dates: &Dates) {
+        for label in self.labels.drain(..) {
+            self.wrapper.remove(&label);
+        }
+
+        let today = dates.today();
+
+        // Next day of every marked event, events are ordered by their date
+        let mut upcoming: Vec<(NaiveDate, &CountdownMark, &Event)> = Vec::new();
+        let mut seen = HashSet::new();
+
+        let events = manager.events_between(today, TimeRangeFilter::now().end(), None);
+
+        for (_, date_time, event) in events {
+            if !seen.insert(event.uid) {
+                continue;
+            }
+
+            if let Some(mark) = self.marks.iter().find(|mark| matches(mark, event)) {
+                upcoming.push((date_time.date(), mark, event));
+            }
+        }
+
+        for (date, mark, event) in upcoming.into_iter().take(self.max_items) {
+            let days = (date - today).num_days();
+            let summary = mark.label.as_deref().unwrap_or(&event.summary);
+
+            let text = match days {
+                0 => tr_args("countdown-today", &[("summary", summary.into())]),
+                1 => tr_args("countdown-tomorrow", &[("summary", summary.into())]),
+                days => tr_args(
+                    "countdown-days",
+                    &[("days", days.into()), ("summary", summary.into())],
+                ),
+            };
+
+            let label = gtk::Label::new(Some(&text));
+            label.add_css_class("calendar-countdown__item");
+            label.set_halign(gtk::Align::Start);
+            label.set_ellipsize(gtk::pango::EllipsizeMode::End);
+
+            self.wrapper.append(&label);
+            self.labels.push(label);
+        }
+
+        self.wrapper.set_visible(!self.labels.is_empty());
+    }
+}
+
+fn matches(mark: &CountdownMark, event: &Event) -> bool {
+    mark.uid.is_some_and(|uid| uid == event.uid)
+        || mark.keyword.as_ref().is_some_and(|keyword| {
+            event
+                .summary
+                .to_lowercase()
+                .contains(&keyword.to_lowercase())
+        })
+}
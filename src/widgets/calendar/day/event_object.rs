@@ -6,7 +6,7 @@ use crate::calendar::Event;
 use glib::Object;
 
 mod imp {
-    use std::cell::RefCell;
+    use std::cell::{Cell, RefCell};
 
     use glib::Properties;
     use gtk::glib;
@@ -23,6 +23,8 @@ mod imp {
         description: RefCell<String>,
         #[property(get, set)]
         color: RefCell<String>,
+        #[property(get, set)]
+        conflict: Cell<bool>,
     }
 
     #[glib::object_subclass]
@@ -40,17 +42,19 @@ glib::wrapper! {
 }
 
 impl EventObject {
-    pub fn new(calendar: &Calendar, event: &Event) -> Self {
+    pub fn new(calendar: &Calendar, event: &Event, conflict: bool) -> Self {
         Object::builder()
             .property("summary", &event.summary)
             .property("description", event.description.as_deref().unwrap_or(""))
             .property("color", calendar.css_color())
+            .property("conflict", conflict)
             .build()
     }
 
-    pub fn update(&self, calendar: &Calendar, event: &Event) {
+    pub fn update(&self, calendar: &Calendar, event: &Event, conflict: bool) {
         self.set_summary(&*event.summary);
         self.set_description(event.description.as_deref().unwrap_or(""));
         self.set_color(calendar.css_color());
+        self.set_conflict(conflict);
     }
 }
@@ -1,15 +1,19 @@
+use std::collections::HashSet;
+
 use gtk::gio;
 
 use crate::calendar::Manager;
 use crate::config::{Config, UuidFilter};
+use crate::messaging::CalendarMessage;
 use crate::prelude::*;
-use crate::widgets::calendar::Dates;
+use crate::widgets::calendar::{Dates, swipe_gesture};
 use crate::widgets::calendar::day::event_object::EventObject;
 
 mod event_object;
 
 pub struct DayWidget {
     filter: Option<UuidFilter>,
+    conflicts: Option<UuidFilter>,
     wrapper: gtk::ScrolledWindow,
     list: gtk::ListView,
 }
@@ -74,6 +78,13 @@ impl DayWidget {
             });
 
             event.set_property("color", event.property_value("color"));
+
+            let wrapper = item.clone();
+            event.connect_notify_local(Some("conflict"), move |event, _| {
+                wrapper.set_class_active("conflict", event.conflict());
+            });
+
+            event.notify("conflict");
         });
 
         let list = gtk::ListView::new(Some(gtk::NoSelection::new(Some(store))), Some(factory));
@@ -83,9 +94,17 @@ impl DayWidget {
         wrapper.set_expand(true);
         wrapper.set_child(Some(&list));
         wrapper.add_css_class("calendar-day");
+        wrapper.add_controller(swipe_gesture(
+            || CalendarMessage::SelectionPrev,
+            || CalendarMessage::SelectionNext,
+        ));
 
         Self {
             filter: config.calendar.day.clone(),
+            conflicts: config
+                .conflicts
+                .as_ref()
+                .map(|conflicts| conflicts.calendars.clone()),
             wrapper,
             list,
         }
@@ -96,9 +115,24 @@ impl DayWidget {
     }
 
     pub fn update_calendar(&mut self, manager: &Manager, dates: &Dates) {
+        let (start, end) = dates.selected_range();
+
+        let conflicts: HashSet<_> = self
+            .conflicts
+            .as_ref()
+            .map(|filter| {
+                manager
+                    .conflicts_between(start, end, Some(filter))
+                    .iter()
+                    .flat_map(|conflict| [conflict.first, conflict.second])
+                    .map(|(_, date_time, event)| (event.uid, *date_time))
+                    .collect()
+            })
+            .unwrap_or_default();
+
         let mut len = 0;
-        for (idx, (calendar, _, event)) in manager
-            .events_between(dates.selected, dates.selected, self.filter.as_ref())
+        for (idx, (calendar, date_time, event)) in manager
+            .events_between(start, end, self.filter.as_ref())
             .enumerate()
         {
             len += 1;
@@ -108,10 +142,14 @@ impl DayWidget {
                     let item = item
                         .downcast::<EventObject>()
                         .expect("The item has to be an EventObject");
-                    item.update(calendar, event);
+                    item.update(calendar, event, conflicts.contains(&(event.uid, *date_time)));
                 }
                 None => {
-                    self.store().append(&EventObject::new(calendar, event));
+                    self.store().append(&EventObject::new(
+                        calendar,
+                        event,
+                        conflicts.contains(&(event.uid, *date_time)),
+                    ));
                 }
             }
         }
@@ -1,24 +1,36 @@
 use chrono::{Duration, NaiveDate};
 use gtk::glib;
 
-use crate::messaging::{self, CalendarMessage};
+use crate::messaging::{self, CalendarMessage, ScreensaverMessage};
 use crate::prelude::*;
+use crate::widgets::calendar::countdown::CountdownWidget;
 use crate::widgets::calendar::day::DayWidget;
 use crate::widgets::calendar::event::EventWidget;
 use crate::widgets::calendar::month::start_grid_date;
+use crate::widgets::calendar::search::SearchWidget;
 use crate::widgets::calendar::selection::SelectionWidget;
 use crate::widgets::calendar::upcoming::UpcomingWidget;
+use crate::widgets::calendar::year::YearWidget;
 use crate::{calendar::Manager, config::Config, widgets::calendar::month::MonthWidget};
 
+pub mod countdown;
 pub mod day;
 pub mod event;
 pub mod month;
+pub mod search;
 pub mod selection;
 pub mod upcoming;
+pub mod year;
+
+/// Minimal horizontal velocity in pixels per second for a swipe to navigate
+const SWIPE_MIN_VELOCITY: f64 = 300.0;
 
 pub struct Dates {
     pub now: DateTime<Local>,
     pub selected: NaiveDate,
+    // Start of the selected week, `selected` stays in the shown month
+    pub selected_week: Option<NaiveDate>,
+    pub first_weekday: Weekday,
 }
 
 impl Dates {
@@ -34,6 +46,20 @@ impl Dates {
         date == self.selected
     }
 
+    pub fn is_selected_week(&self, date: NaiveDate) -> bool {
+        let (start, end) = self.selected_range();
+
+        self.selected_week.is_some() && date >= start && date <= end
+    }
+
+    /// First and last day of the current selection, either a single day or a whole week
+    pub fn selected_range(&self) -> (NaiveDate, NaiveDate) {
+        match self.selected_week {
+            Some(week) => (week, week + Duration::days(6)),
+            None => (self.selected, self.selected),
+        }
+    }
+
     pub fn is_month(&self, date: NaiveDate) -> bool {
         date.month() == self.selected.month()
     }
@@ -46,6 +72,10 @@ pub struct CalendarWidget {
     selection: SelectionWidget,
     event: EventWidget,
     upcoming: UpcomingWidget,
+    year: YearWidget,
+    countdown: CountdownWidget,
+    screensaver_countdown: CountdownWidget,
+    search: SearchWidget,
 
     dates: Dates,
     manager: Manager,
@@ -58,6 +88,8 @@ impl CalendarWidget {
         let dates = Dates {
             now: chrono::Local::now(),
             selected: chrono::Local::now().naive_local().date(),
+            selected_week: None,
+            first_weekday: config.calendar.first_weekday(),
         };
 
         let month = MonthWidget::new(config, &dates);
@@ -65,11 +97,17 @@ impl CalendarWidget {
         let selection = SelectionWidget::new(config);
         let event = EventWidget::new(config);
         let upcoming = UpcomingWidget::new(config);
+        let year = YearWidget::new(config, &dates);
+        let countdown = CountdownWidget::new(config);
+        let screensaver_countdown = CountdownWidget::new(config);
+        let search = SearchWidget::new(config);
 
         let wrapper = gtk::Box::new(gtk::Orientation::Vertical, 16);
+        wrapper.append(search.widget());
         wrapper.append(month.widget());
         wrapper.append(selection.widget());
         wrapper.append(day.widget());
+        wrapper.append(countdown.widget());
         wrapper.append(event.widget());
 
         glib::timeout_add_seconds(600, move || {
@@ -88,8 +126,12 @@ impl CalendarWidget {
             selection,
             event,
             upcoming,
+            year,
+            countdown,
+            screensaver_countdown,
+            search,
 
-            manager: Manager::new(config.ical.clone()),
+            manager: Manager::new(config),
             reset_dates_timeout: None,
             next_day_timeout: None,
             dates,
@@ -104,14 +146,22 @@ impl CalendarWidget {
         &self.upcoming
     }
 
+    pub const fn screensaver_countdown(&self) -> &CountdownWidget {
+        &self.screensaver_countdown
+    }
+
+    pub const fn year(&self) -> &YearWidget {
+        &self.year
+    }
+
     pub fn update(&mut self, message: CalendarMessage) {
         match message {
             CalendarMessage::Fetch => {
-                let client = self.manager.client.clone();
+                let sources = self.manager.sources.clone();
 
                 log::info!("Calendar: fetching calendar map");
 
-                gtk::gio::spawn_blocking(move || match client.get_map() {
+                gtk::gio::spawn_blocking(move || match sources.fetch() {
                     Err(err) => log::error!("Calendar: failed to fetch map: {err:?}"),
                     Ok(map) => {
                         log::info!(
@@ -136,6 +186,7 @@ impl CalendarWidget {
             CalendarMessage::SelectNow => {
                 self.dates.now = chrono::Local::now();
                 self.dates.selected = self.dates.now.date_naive();
+                self.dates.selected_week = None;
 
                 log::info!("Calendar: selected now {}", self.dates.now);
 
@@ -145,30 +196,76 @@ impl CalendarWidget {
             }
             CalendarMessage::SelectDate(date) => {
                 self.dates.selected = date;
+                self.dates.selected_week = None;
                 self.reset_dates_timeout();
 
                 self.update_calendar();
             }
+            CalendarMessage::SelectionPrev => {
+                let (start, end) = self.dates.selected_range();
+
+                self.move_selection(-((end - start).num_days() + 1));
+            }
+            CalendarMessage::SelectionNext => {
+                let (start, end) = self.dates.selected_range();
+
+                self.move_selection((end - start).num_days() + 1);
+            }
+            CalendarMessage::MoveSelection(days) => self.move_selection(days),
             CalendarMessage::ToggleCalendar(uid) => {
                 self.manager.toggle_calendar(uid);
 
-                log::info!("Calendar: toggled \"{}\"", self.manager.calendar_name(&uid).unwrap_or_else(|| uid.to_string()));
+                log::info!(
+                    "Calendar: toggled \"{}\"",
+                    self.manager
+                        .calendar_name(&uid)
+                        .unwrap_or_else(|| uid.to_string())
+                );
 
                 self.update_calendar();
             }
+            CalendarMessage::Search(query) => {
+                log::debug!("Calendar: searching \"{query}\"");
+
+                self.search.search(query, &self.manager, &self.dates);
+            }
             CalendarMessage::SelectGridIndex(idx) => {
-                let selected_date = start_grid_date(self.dates.selected) + Duration::days(idx as i64);
+                let selected_date = start_grid_date(self.dates.selected, self.dates.first_weekday)
+                    + Duration::days(idx as i64);
 
                 log::info!("Calendar: selected date {}", selected_date);
 
                 messaging::send_message(CalendarMessage::SelectDate(selected_date));
             }
+            CalendarMessage::SelectGridWeek(row) => {
+                let week = start_grid_date(self.dates.selected, self.dates.first_weekday)
+                    + Duration::days(row as i64 * 7);
+
+                // Select a day of the week in the shown month, so the grid does not move
+                let month_start = self.dates.selected.with_day(1).unwrap();
+                let month_end = month_start + chrono::Months::new(1) - Duration::days(1);
+
+                self.dates.selected = week.clamp(month_start, month_end);
+                self.dates.selected_week = Some(week);
+
+                log::info!("Calendar: selected week {}", week.iso_week().week());
+
+                self.reset_dates_timeout();
+                self.update_calendar();
+            }
             CalendarMessage::MonthPrev => messaging::send_message(CalendarMessage::SelectDate(
                 self.dates.selected - chrono::Months::new(1),
             )),
             CalendarMessage::MonthNext => messaging::send_message(CalendarMessage::SelectDate(
                 self.dates.selected + chrono::Months::new(1),
             )),
+            CalendarMessage::YearShown => self.year.update_calendar(&self.manager, &self.dates),
+            CalendarMessage::YearPrev => messaging::send_message(CalendarMessage::SelectDate(
+                self.dates.selected - chrono::Months::new(12),
+            )),
+            CalendarMessage::YearNext => messaging::send_message(CalendarMessage::SelectDate(
+                self.dates.selected + chrono::Months::new(12),
+            )),
         }
     }
 
@@ -178,10 +275,28 @@ impl CalendarWidget {
         self.selection.update_calendar(&self.manager);
         self.event.update_calendar(&self.manager, &self.dates);
         self.upcoming.update_calendar(&self.manager, &self.dates);
+        self.year.update_calendar(&self.manager, &self.dates);
+        self.countdown.update_calendar(&self.manager, &self.dates);
+        self.screensaver_countdown
+            .update_calendar(&self.manager, &self.dates);
+        self.search.update_calendar(&self.manager, &self.dates);
 
         log::info!("Calendar: updated for date {}", self.dates.selected);
     }
 
+    fn move_selection(&mut self, days: i64) {
+        self.dates.selected += Duration::days(days);
+        self.dates.selected_week = self
+            .dates
+            .selected_week
+            .map(|week| week + Duration::days(days));
+
+        log::info!("Calendar: moved selection to {}", self.dates.selected);
+
+        self.reset_dates_timeout();
+        self.update_calendar();
+    }
+
     fn reset_dates_timeout(&mut self) {
         remove_source(self.reset_dates_timeout.take());
 
@@ -208,3 +323,71 @@ impl CalendarWidget {
     }
 }
 
+/// Navigate by swiping horizontally, swiping to the left shows what comes next.
+pub fn swipe_gesture(
+    prev: fn() -> CalendarMessage,
+    next: fn() -> CalendarMessage,
+) -> gtk::GestureSwipe {
+    let gesture = gtk::GestureSwipe::new();
+    gesture.set_touch_only(false);
+    gesture.connect_swipe(move |_, velocity_x, velocity_y| {
+        if velocity_x.abs() < SWIPE_MIN_VELOCITY || velocity_x.abs() < velocity_y.abs() * 2.0 {
+            return;
+        }
+
+        messaging::send_message(if velocity_x < 0.0 { next() } else { prev() });
+    });
+
+    gesture
+}
+
+/// Keyboard navigation for remote keypads and keyboards: arrow keys move the selection,
+/// page up and down switch the month and `t` or home select today.
+/// Keys are only handled while the calendar page of the stack is shown.
+pub fn key_controller(stack: &gtk::Stack) -> gtk::EventControllerKey {
+    let controller = gtk::EventControllerKey::new();
+    // Arrow keys would otherwise move the focus between buttons
+    controller.set_propagation_phase(gtk::PropagationPhase::Capture);
+    controller.connect_key_pressed(glib::clone!(
+        #[weak]
+        stack,
+        #[upgrade_or]
+        glib::Propagation::Proceed,
+        move |controller, key, _, _| {
+            use gtk::gdk::Key;
+
+            if stack.visible_child_name().as_deref() != Some("calendar") {
+                return glib::Propagation::Proceed;
+            }
+
+            // Leave typing to text inputs like the search
+            let typing = controller
+                .widget()
+                .and_then(|widget| widget.root())
+                .and_then(|root| root.focus())
+                .is_some_and(|focus| focus.is::<gtk::Text>());
+
+            if typing {
+                return glib::Propagation::Proceed;
+            }
+
+            let message = match key {
+                Key::Left => CalendarMessage::SelectionPrev,
+                Key::Right => CalendarMessage::SelectionNext,
+                Key::Up => CalendarMessage::MoveSelection(-7),
+                Key::Down => CalendarMessage::MoveSelection(7),
+                Key::Page_Up => CalendarMessage::MonthPrev,
+                Key::Page_Down => CalendarMessage::MonthNext,
+                Key::t | Key::T | Key::Home => CalendarMessage::SelectNow,
+                _ => return glib::Propagation::Proceed,
+            };
+
+            messaging::send_message(ScreensaverMessage::Reset);
+            messaging::send_message(message);
+
+            glib::Propagation::Stop
+        }
+    ));
+
+    controller
+}
@@ -0,0 +1,267 @@
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+use uuid::Uuid;
+
+use crate::calendar::{Calendar, Event, Manager};
+use crate::config::UuidFilter;
+
+/// Number of bars stacked inside one grid day before the rest is hidden behind "+N"
+pub const MAX_LANES: usize = 2;
+
+#[derive(Debug)]
+pub struct Bar {
+    pub row: usize,
+    pub column: usize,
+    pub width: usize,
+    pub lane: usize,
+    pub title: String,
+    pub color: String,
+    pub fg_color: String,
+    pub continues_before: bool,
+    pub continues_after: bool,
+}
+
+#[derive(Debug, Default)]
+pub struct BarLayout {
+    pub bars: Vec<Bar>,
+    pub overflow: BTreeMap<NaiveDate, usize>,
+}
+
+struct Segment<'a> {
+    calendar: &'a Calendar,
+    event: &'a Event,
+    start: NaiveDate,
+    end: NaiveDate,
+    continues_before: bool,
+    continues_after: bool,
+}
+
+impl BarLayout {
+    pub fn new(manager: &Manager, grid_start: NaiveDate, filter: Option<&UuidFilter>) -> Self {
+        let grid_end = grid_start + super::DURATION;
+
+        // Each recurrence gets its own bar, holidays are shown by the grid day itself
+        let mut occurrences: BTreeMap<(NaiveDateTime, Uuid), (&Calendar, &Event)> = BTreeMap::new();
+
+        for (calendar, date_time, event) in manager.events_between(grid_start, grid_end, filter) {
+            if let Some(start) = manager.occurrence_start(&event.uid, date_time)
+                && !calendar.kind.is_holiday()
+            {
+                occurrences
+                    .entry((*start, event.uid))
+                    .or_insert((calendar, event));
+            }
+        }
+
+        Self::from_occurrences(
+            grid_start,
+            occurrences
+                .into_iter()
+                .map(|((start, _), (calendar, event))| (start, calendar, event)),
+        )
+    }
+
+    /// Lays out occurrences ordered by their start, single-day events become one column wide bars
+    fn from_occurrences<'a>(
+        grid_start: NaiveDate,
+        occurrences: impl IntoIterator<Item = (NaiveDateTime, &'a Calendar, &'a Event)>,
+    ) -> Self {
+        let grid_end = grid_start + super::DURATION;
+        let mut rows: [Vec<Segment>; 6] = Default::default();
+
+        for (occurrence_start, calendar, event) in occurrences {
+            let first = occurrence_start.date();
+            let last = event.occurrence_end_date(occurrence_start);
+
+            let start = first.max(grid_start);
+            let end = last.min(grid_end);
+            let mut segment_start = start;
+
+            while segment_start <= end {
+                let row = row_of(grid_start, segment_start);
+                let row_end = grid_start + Duration::days(row as i64 * 7 + 6);
+                let segment_end = end.min(row_end);
+
+                rows[row].push(Segment {
+                    calendar,
+                    event,
+                    start: segment_start,
+                    end: segment_end,
+                    continues_before: segment_start != start || first < start,
+                    continues_after: segment_end != end || last > end,
+                });
+
+                segment_start = segment_end + Duration::days(1);
+            }
+        }
+
+        let mut layout = Self::default();
+
+        for (row, mut segments) in rows.into_iter().enumerate() {
+            segments.sort_by_key(|s| (s.start, Reverse(s.end)));
+
+            let mut lanes: Vec<[bool; 7]> = Vec::new();
+
+            for segment in segments {
+                let first = column_of(grid_start, segment.start);
+                let last = column_of(grid_start, segment.end);
+
+                let lane = (0..)
+                    .find(|lane| {
+                        lanes
+                            .get(*lane)
+                            .is_none_or(|used| used[first..=last].iter().all(|used| !used))
+                    })
+                    .expect("There is always a free lane");
+
+                if lane >= lanes.len() {
+                    lanes.push([false; 7]);
+                }
+                lanes[lane][first..=last].fill(true);
+
+                if lane >= MAX_LANES {
+                    for date in segment.start.iter_days().take(last - first + 1) {
+                        *layout.overflow.entry(date).or_default() += 1;
+                    }
+
+                    continue;
+                }
+
+                layout.bars.push(Bar {
+                    row,
+                    column: first,
+                    width: last - first + 1,
+                    lane,
+                    title: segment.event.summary.clone(),
+                    color: segment.calendar.css_color(),
+                    fg_color: segment.calendar.css_fg_color(),
+                    continues_before: segment.continues_before,
+                    continues_after: segment.continues_after,
+                });
+            }
+        }
+
+        layout
+    }
+}
+
+fn row_of(grid_start: NaiveDate, date: NaiveDate) -> usize {
+    ((date - grid_start).num_days() / 7) as usize
+}
+
+fn column_of(grid_start: NaiveDate, date: NaiveDate) -> usize {
+    ((date - grid_start).num_days() % 7) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use palette::rgb::Rgb;
+    use url::Url;
+
+    use super::*;
+    use crate::calendar::{CalendarKind, IndicatorShape};
+
+    /// Monday of the first grid row
+    const GRID_START: NaiveDate = NaiveDate::from_ymd_opt(2026, 3, 2).unwrap();
+
+    fn date_time(value: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    fn event(summary: &str, start: &str, end: &str) -> Event {
+        Event {
+            etag: String::new(),
+            uid: Uuid::new_v5(&Uuid::NAMESPACE_URL, summary.as_bytes()),
+            summary: summary.to_string(),
+            description: None,
+            location: None,
+            start: date_time(start),
+            end: date_time(end),
+            url: Url::parse("https://example.com/event.ics").unwrap(),
+            rrule: None,
+        }
+    }
+
+    fn layout(events: &[Event]) -> BarLayout {
+        let calendar = Calendar {
+            uid: Uuid::new_v5(&Uuid::NAMESPACE_URL, b"https://example.com/calendar"),
+            url_str: "https://example.com/calendar".to_string(),
+            name: "Calendar".to_string(),
+            color: Rgb::new(0, 0, 0),
+            kind: CalendarKind::CalDav,
+            shape: IndicatorShape::default(),
+        };
+
+        BarLayout::from_occurrences(
+            GRID_START,
+            events.iter().map(|event| (event.start, &calendar, event)),
+        )
+    }
+
+    fn bars(layout: &BarLayout) -> Vec<(&str, usize, usize, usize, usize, bool, bool)> {
+        layout
+            .bars
+            .iter()
+            .map(|bar| {
+                (
+                    bar.title.as_str(),
+                    bar.row,
+                    bar.column,
+                    bar.width,
+                    bar.lane,
+                    bar.continues_before,
+                    bar.continues_after,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn single_day_event() {
+        let layout = layout(&[event("Dentist", "2026-03-04 10:00", "2026-03-04 11:00")]);
+
+        assert_eq!(bars(&layout), [("Dentist", 0, 2, 1, 0, false, false)]);
+        assert!(layout.overflow.is_empty());
+    }
+
+    #[test]
+    fn event_crossing_a_week_row() {
+        let layout = layout(&[
+            event("Before", "2026-02-27 00:00", "2026-03-04 00:00"),
+            event("Trip", "2026-03-06 08:00", "2026-03-10 18:00"),
+        ]);
+
+        assert_eq!(
+            bars(&layout),
+            [
+                ("Before", 0, 0, 2, 0, true, false),
+                ("Trip", 0, 4, 3, 0, false, true),
+                ("Trip", 1, 0, 2, 0, true, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn lane_overflow() {
+        let layout = layout(&[
+            event("Trip", "2026-03-03 08:00", "2026-03-05 18:00"),
+            event("Breakfast", "2026-03-04 08:00", "2026-03-04 09:00"),
+            event("Lunch", "2026-03-04 12:00", "2026-03-04 13:00"),
+            event("Dinner", "2026-03-04 19:00", "2026-03-04 21:00"),
+        ]);
+
+        assert_eq!(
+            bars(&layout),
+            [
+                ("Trip", 0, 1, 3, 0, false, false),
+                ("Breakfast", 0, 2, 1, 1, false, false),
+            ]
+        );
+        assert_eq!(
+            layout.overflow,
+            BTreeMap::from([(NaiveDate::from_ymd_opt(2026, 3, 4).unwrap(), 2)])
+        );
+    }
+}
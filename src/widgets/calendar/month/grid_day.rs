@@ -2,6 +2,8 @@ use std::collections::BTreeMap;
 
 use chrono::{Datelike, Duration, NaiveDate};
 
+use crate::calendar::{CalendarKind, IndicatorShape};
+use crate::config::MonthLayout;
 use crate::messaging;
 use crate::prelude::*;
 use crate::widgets::calendar::Dates;
@@ -11,12 +13,13 @@ pub struct GridDayWidget {
     idx: usize,
     wrapper: gtk::Box,
     label: gtk::Label,
+    overflow: gtk::Label,
     indicator_wrapper: gtk::Box,
     indicators: BTreeMap<uuid::Uuid, gtk::Box>,
 }
 
 impl GridDayWidget {
-    pub fn new(idx: usize) -> Self {
+    pub fn new(idx: usize, layout: MonthLayout) -> Self {
         let clickable = gtk::GestureClick::new();
         clickable.connect_pressed(move |controller, _, _, _| {
             if controller.current_button() == gtk::gdk::BUTTON_PRIMARY {
@@ -30,6 +33,12 @@ impl GridDayWidget {
         label.set_valign(gtk::Align::End);
         label.add_css_class("calendar-month__grid-day__label");
 
+        let overflow = gtk::Label::new(None);
+        overflow.add_css_class("calendar-month__grid-day__overflow");
+        overflow.set_halign(gtk::Align::End);
+        overflow.set_valign(gtk::Align::Start);
+        overflow.set_visible(false);
+
         let indicator_wrapper = gtk::Box::new(gtk::Orientation::Horizontal, 4);
         indicator_wrapper.set_valign(gtk::Align::Start);
         indicator_wrapper.set_vexpand(true);
@@ -44,14 +53,37 @@ impl GridDayWidget {
         let wrapper = gtk::Box::new(gtk::Orientation::Vertical, 0);
         wrapper.set_expand(true);
         wrapper.add_css_class("calendar-month__grid-day");
-        wrapper.append(&label);
-        wrapper.append(&indicator_center);
         wrapper.add_controller(clickable);
 
+        match layout {
+            MonthLayout::Dots => {
+                wrapper.append(&label);
+                wrapper.append(&indicator_center);
+            }
+            MonthLayout::Bars => {
+                label.set_expand(false);
+                label.set_halign(gtk::Align::Start);
+                label.set_valign(gtk::Align::Start);
+
+                let header = gtk::Box::new(gtk::Orientation::Horizontal, 4);
+                header.set_hexpand(true);
+                header.set_vexpand(true);
+                header.set_valign(gtk::Align::Start);
+                header.append(&label);
+                header.append(&overflow);
+
+                overflow.set_hexpand(true);
+
+                wrapper.add_css_class("bars");
+                wrapper.append(&header);
+            }
+        }
+
         Self {
             idx,
             wrapper,
             label,
+            overflow,
             indicator_wrapper,
             indicators: BTreeMap::new(),
         }
@@ -73,6 +105,8 @@ impl GridDayWidget {
         self.wrapper.remove_css_class("selected");
         self.wrapper.remove_css_class("today");
         self.wrapper.remove_css_class("not-month");
+        self.wrapper
+            .set_class_active("selected-week", dates.is_selected_week(date));
 
         if dates.is_selected(date) {
             self.wrapper.add_css_class("selected");
@@ -95,7 +129,7 @@ impl GridDayWidget {
             }
         }
 
-        for (uid, color) in indicator_map
+        for (uid, (color, shape)) in indicator_map
             .get(&date)
             .map(|i| i.iter())
             .unwrap_or_default()
@@ -103,13 +137,49 @@ impl GridDayWidget {
             if !self.indicators.contains_key(uid) {
                 let indicator = gtk::Box::new(gtk::Orientation::Horizontal, 0);
                 indicator.add_css_class("calendar-month__grid-day__indicator");
+                indicator.add_css_class(shape.css_class());
                 indicator.set_width_request(12);
                 indicator.set_height_request(12);
-                indicator.inline_css(&format!("background-color: {color}"));
+
+                match shape {
+                    IndicatorShape::Ring => indicator.inline_css(&format!("border-color: {color}")),
+                    _ => indicator.inline_css(&format!("background-color: {color}")),
+                }
 
                 self.indicator_wrapper.append(&indicator);
                 self.indicators.insert(*uid, indicator);
             }
         }
     }
+
+    pub fn set_holidays(&self, holidays: &super::HolidayMap, grid_start: NaiveDate) {
+        let date = grid_start + Duration::days(self.idx as i64);
+        let kinds = holidays.get(&date);
+
+        self.wrapper.set_class_active(
+            "holiday",
+            kinds.is_some_and(|kinds| kinds.contains_key(&CalendarKind::Holidays)),
+        );
+        self.wrapper.set_class_active(
+            "vacation",
+            kinds.is_some_and(|kinds| kinds.contains_key(&CalendarKind::SchoolVacations)),
+        );
+
+        let names = kinds
+            .map(|kinds| kinds.values().flatten().cloned().collect::<Vec<_>>().join("\n"));
+
+        self.wrapper.set_tooltip_text(names.as_deref());
+    }
+
+    pub fn set_overflow(&self, overflow: &BTreeMap<NaiveDate, usize>, grid_start: NaiveDate) {
+        let date = grid_start + Duration::days(self.idx as i64);
+
+        match overflow.get(&date) {
+            Some(count) => {
+                self.overflow.set_label(&format!("+{count}"));
+                self.overflow.set_visible(true);
+            }
+            None => self.overflow.set_visible(false),
+        }
+    }
 }
@@ -1,28 +1,40 @@
 use std::collections::BTreeMap;
 
-use chrono::{Datelike, Duration, Locale, NaiveDate};
+use chrono::{Datelike, Duration, NaiveDate};
 
-use crate::calendar::Manager;
-use crate::config::{Config, UuidFilter};
+use crate::calendar::{CalendarKind, IndicatorShape, Manager};
+use crate::config::{Config, MonthLayout, UuidFilter};
+use crate::i18n::{self, tr};
 use crate::messaging::{self, CalendarMessage};
 use crate::prelude::*;
-use crate::widgets::calendar::Dates;
+use crate::widgets::calendar::{Dates, swipe_gesture};
+use crate::widgets::calendar::month::bars::BarLayout;
 use crate::widgets::calendar::month::grid_day::GridDayWidget;
 
+mod bars;
 mod grid_day;
 
-pub type IndicatorMap = BTreeMap<NaiveDate, BTreeMap<uuid::Uuid, String>>;
+pub type IndicatorMap = BTreeMap<NaiveDate, BTreeMap<uuid::Uuid, (String, IndicatorShape)>>;
+pub type HolidayMap = BTreeMap<NaiveDate, BTreeMap<CalendarKind, Vec<String>>>;
 
 const DURATION: chrono::TimeDelta = Duration::days(41);
 
+// Offset of the first bar from the top of a grid day (padding + day label)
+const BAR_OFFSET: i32 = 30;
+const BAR_HEIGHT: i32 = 18;
+
 pub struct MonthWidget {
     wrapper: gtk::Box,
 
     filter: Option<UuidFilter>,
+    layout: MonthLayout,
 
     label: gtk::Label,
+    month_grid: gtk::Grid,
     grid: [GridDayWidget; 42],
+    week_numbers: Option<[gtk::Label; 6]>,
     grid_start: NaiveDate,
+    bars: Vec<gtk::Label>,
 }
 
 impl MonthWidget {
@@ -55,8 +67,16 @@ impl MonthWidget {
         let weekdays = gtk::Box::new(gtk::Orientation::Horizontal, 0);
         weekdays.set_hexpand(true);
 
-        for day in ["Mo", "Di", "Mi", "Do", "Fr", "Sa", "So"] {
-            let label = gtk::Label::new(Some(day));
+        if config.calendar.week_numbers {
+            let label = gtk::Label::new(None);
+            label.add_css_class("calendar-month__weekday-label");
+            label.set_hexpand(true);
+
+            weekdays.append(&label);
+        }
+
+        for day in i18n::weekday_labels(dates.first_weekday) {
+            let label = gtk::Label::new(Some(&day));
             label.add_css_class("calendar-month__weekday-label");
             label.set_halign(gtk::Align::Center);
             label.set_hexpand(true);
@@ -74,12 +94,29 @@ impl MonthWidget {
             .column_spacing(8)
             .build();
 
+        let column_offset = i32::from(config.calendar.week_numbers);
+
         let mut grid_elements = Vec::new();
+        let mut week_numbers = Vec::new();
         for row_idx in 0..6 {
+            if config.calendar.week_numbers {
+                let week_number = create_week_number(row_idx);
+
+                month_grid.attach(&week_number, 0, row_idx as i32 + 1, 1, 1);
+
+                week_numbers.push(week_number);
+            }
+
             for column_idx in 0..7 {
-                let day = GridDayWidget::new(row_idx * 7 + column_idx);
+                let day = GridDayWidget::new(row_idx * 7 + column_idx, config.calendar.month_layout);
 
-                month_grid.attach(day.widget(), column_idx as i32, row_idx as i32 + 1, 1, 1);
+                month_grid.attach(
+                    day.widget(),
+                    column_idx as i32 + column_offset,
+                    row_idx as i32 + 1,
+                    1,
+                    1,
+                );
 
                 grid_elements.push(day);
             }
@@ -92,13 +129,21 @@ impl MonthWidget {
         wrapper.append(&control_wrapper);
         wrapper.append(&weekdays);
         wrapper.append(&month_grid);
+        wrapper.add_controller(swipe_gesture(
+            || CalendarMessage::MonthPrev,
+            || CalendarMessage::MonthNext,
+        ));
 
         Self {
             filter: config.calendar.month.clone(),
+            layout: config.calendar.month_layout,
             label,
             wrapper,
+            month_grid,
             grid: grid_elements.try_into().expect("Month grid has wrong size"),
-            grid_start: start_grid_date(dates.selected),
+            week_numbers: week_numbers.try_into().ok(),
+            grid_start: start_grid_date(dates.selected, dates.first_weekday),
+            bars: Vec::new(),
         }
     }
 
@@ -107,42 +152,153 @@ impl MonthWidget {
     }
 
     pub fn update_calendar(&mut self, manager: &Manager, dates: &Dates) {
-        self.grid_start = start_grid_date(dates.selected);
+        self.grid_start = start_grid_date(dates.selected, dates.first_weekday);
         self.label.set_label(
             &dates
                 .selected
-                .format_localized("%B %Y", Locale::de_DE)
+                .format_localized(&tr("format-month-year"), i18n::locale())
                 .to_string(),
         );
 
-        let indicators = manager
-            .calendars_between(
+        if let Some(week_numbers) = &self.week_numbers {
+            for (row, label) in week_numbers.iter().enumerate() {
+                let week_start = self.grid_start + Duration::days(row as i64 * 7);
+
+                label.set_label(&iso_week(week_start).to_string());
+                label.set_class_active("selected", dates.is_selected_week(week_start));
+            }
+        }
+
+        let holidays = manager
+            .events_between(
                 self.grid_start,
                 self.grid_start + DURATION,
                 self.filter.as_ref(),
             )
+            .filter(|(calendar, _, _)| calendar.kind.is_holiday())
             .fold(
-                BTreeMap::new(),
-                |mut map: IndicatorMap, (event_start, calendar)| {
+                HolidayMap::new(),
+                |mut map, (calendar, event_start, event)| {
                     map.entry(event_start.date())
                         .or_default()
-                        .entry(calendar.uid)
-                        .or_insert_with(|| calendar.css_color());
+                        .entry(calendar.kind)
+                        .or_default()
+                        .push(event.summary.clone());
 
                     map
                 },
             );
 
-        for grid_day in &mut self.grid {
-            grid_day.update_calendar(&indicators, dates, self.grid_start);
+        match self.layout {
+            MonthLayout::Dots => {
+                let indicators = manager
+                    .calendars_between(
+                        self.grid_start,
+                        self.grid_start + DURATION,
+                        self.filter.as_ref(),
+                    )
+                    // Holidays are shown by the grid day itself
+                    .filter(|(_, calendar)| !calendar.kind.is_holiday())
+                    .fold(
+                        BTreeMap::new(),
+                        |mut map: IndicatorMap, (event_start, calendar)| {
+                            map.entry(event_start.date())
+                                .or_default()
+                                .entry(calendar.uid)
+                                .or_insert_with(|| (calendar.css_color(), calendar.shape));
+
+                            map
+                        },
+                    );
+
+                for grid_day in &mut self.grid {
+                    grid_day.update_calendar(&indicators, dates, self.grid_start);
+                    grid_day.set_holidays(&holidays, self.grid_start);
+                }
+            }
+            MonthLayout::Bars => {
+                let layout = BarLayout::new(manager, self.grid_start, self.filter.as_ref());
+
+                for grid_day in &mut self.grid {
+                    grid_day.update_calendar(&IndicatorMap::new(), dates, self.grid_start);
+                    grid_day.set_overflow(&layout.overflow, self.grid_start);
+                    grid_day.set_holidays(&holidays, self.grid_start);
+                }
+
+                self.update_bars(&layout);
+            }
         }
     }
+
+    fn update_bars(&mut self, layout: &BarLayout) {
+        for bar in self.bars.drain(..) {
+            self.month_grid.remove(&bar);
+        }
+
+        for bar in &layout.bars {
+            let label = gtk::Label::new(Some(&bar.title));
+            label.add_css_class("calendar-month__bar");
+            label.set_class_active("continues-before", bar.continues_before);
+            label.set_class_active("continues-after", bar.continues_after);
+            label.set_ellipsize(gtk::pango::EllipsizeMode::End);
+            label.set_max_width_chars(1);
+            label.set_xalign(0.0);
+            label.set_hexpand(true);
+            label.set_valign(gtk::Align::Start);
+            label.set_margin_top(BAR_OFFSET + bar.lane as i32 * BAR_HEIGHT);
+            // Taps fall through to the grid day below
+            label.set_can_target(false);
+            label.inline_css(&format!(
+                "background-color: {}; color: {}",
+                bar.color, bar.fg_color
+            ));
+
+            self.month_grid.attach(
+                &label,
+                bar.column as i32 + i32::from(self.week_numbers.is_some()),
+                bar.row as i32 + 1,
+                bar.width as i32,
+                1,
+            );
+
+            self.bars.push(label);
+        }
+    }
+}
+
+fn create_week_number(row_idx: usize) -> gtk::Label {
+    let clickable = gtk::GestureClick::new();
+    clickable.connect_pressed(move |controller, _, _, _| {
+        if controller.current_button() == gtk::gdk::BUTTON_PRIMARY {
+            messaging::send_message(CalendarMessage::SelectGridWeek(row_idx));
+        }
+    });
+
+    let label = gtk::Label::new(None);
+    label.add_css_class("calendar-month__week-number");
+    label.set_expand(true);
+    label.set_align(gtk::Align::Center);
+    label.add_controller(clickable);
+
+    label
+}
+
+/// ISO week number of the week starting at the given date.
+/// The monday decides, as weeks not starting on mondays overlap two ISO weeks.
+fn iso_week(week_start: NaiveDate) -> u32 {
+    week_start
+        .iter_days()
+        .take(7)
+        .find(|date| date.weekday() == chrono::Weekday::Mon)
+        .unwrap_or(week_start)
+        .iso_week()
+        .week()
 }
 
-pub fn start_grid_date(date: NaiveDate) -> NaiveDate {
+pub fn start_grid_date(date: NaiveDate, first_weekday: chrono::Weekday) -> NaiveDate {
     let mut first = date.with_day(1).unwrap();
 
-    while first.weekday() != chrono::Weekday::Mon {
+    while first.weekday() != first_weekday {
         first = first.pred_opt().unwrap_or(first);
     }
 
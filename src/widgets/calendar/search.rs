@@ -0,0 +1,143 @@
+use crate::calendar::Manager;
+use crate::config::{Config, UuidFilter};
+use crate::i18n::{self, tr};
+use crate::messaging::{self, CalendarMessage};
+use crate::prelude::*;
+use crate::widgets::calendar::Dates;
+
+const MAX_RESULTS: usize = 50;
+
+pub struct SearchWidget {
+    filter: Option<UuidFilter>,
+    query: String,
+
+    wrapper: gtk::Box,
+    popover: gtk::Popover,
+    results: gtk::Box,
+}
+
+impl SearchWidget {
+    pub fn new(config: &Config) -> Self {
+        let entry = gtk::SearchEntry::new();
+        entry.add_css_class("calendar-search__entry");
+        entry.set_hexpand(true);
+        entry.set_placeholder_text(Some(&tr("search-placeholder")));
+        entry.connect_search_changed(|entry| {
+            messaging::send_message(CalendarMessage::Search(entry.text().to_string()));
+        });
+
+        let results = gtk::Box::new(gtk::Orientation::Vertical, 4);
+        results.add_css_class("calendar-search__results");
+
+        let scrolled = gtk::ScrolledWindow::new();
+        scrolled.set_propagate_natural_height(true);
+        scrolled.set_max_content_height(400);
+        scrolled.set_min_content_width(400);
+        scrolled.set_child(Some(&results));
+
+        let popover = gtk::Popover::new();
+        popover.add_css_class("calendar-search__popover");
+        popover.set_autohide(false);
+        popover.set_has_arrow(false);
+        popover.set_position(gtk::PositionType::Bottom);
+        popover.set_child(Some(&scrolled));
+
+        let wrapper = gtk::Box::new(gtk::Orientation::Horizontal, 0);
+        wrapper.add_css_class("calendar-search");
+        wrapper.append(&entry);
+        popover.set_parent(&wrapper);
+
+        Self {
+            filter: config.calendar.search.clone(),
+            query: String::new(),
+            wrapper,
+            popover,
+            results,
+        }
+    }
+
+    pub const fn widget(&self) -> &gtk::Box {
+        &self.wrapper
+    }
+
+    pub fn search(&mut self, query: String, manager: &Manager, dates: &Dates) {
+        self.query = query;
+
+        if self.query.trim().is_empty() {
+            self.popover.popdown();
+        } else {
+            self.render(manager, dates);
+            self.popover.popup();
+        }
+    }
+
+    pub fn update_calendar(&self, manager: &Manager, dates: &Dates) {
+        if self.popover.is_visible() {
+            self.render(manager, dates);
+        }
+    }
+
+    fn render(&self, manager: &Manager, dates: &Dates) {
+        while let Some(child) = self.results.first_child() {
+            self.results.remove(&child);
+        }
+
+        let mut count = 0;
+        for (calendar, date_time, event) in manager
+            .search(&self.query, dates.today(), self.filter.as_ref())
+            .take(MAX_RESULTS)
+        {
+            count += 1;
+
+            let date = date_time.date();
+
+            let indicator = gtk::Box::new(gtk::Orientation::Vertical, 0);
+            indicator.add_css_class("calendar-search__item__indicator");
+            indicator.set_width_request(12);
+            indicator.inline_css(&format!("background-color: {}", calendar.css_color()));
+
+            let date_label = gtk::Label::new(Some(
+                &date
+                    .format_localized(&tr("format-search-date"), i18n::locale())
+                    .to_string(),
+            ));
+            date_label.add_css_class("calendar-search__item__date");
+            date_label.set_xalign(0.0);
+
+            let summary = gtk::Label::new(Some(&event.summary));
+            summary.add_css_class("calendar-search__item__summary");
+            summary.set_hexpand(true);
+            summary.set_xalign(0.0);
+            summary.set_ellipsize(gtk::pango::EllipsizeMode::End);
+
+            let content = gtk::Box::new(gtk::Orientation::Horizontal, 8);
+            content.append(&indicator);
+            content.append(&date_label);
+            content.append(&summary);
+
+            let button = gtk::Button::new();
+            button.add_css_class("calendar-search__item");
+            button.set_class_active("past", date < dates.today());
+            button.set_child(Some(&content));
+            button.connect_clicked(move |button| {
+                messaging::send_message(CalendarMessage::SelectDate(date));
+
+                if let Some(popover) = button
+                    .ancestor(gtk::Popover::static_type())
+                    .and_downcast::<gtk::Popover>()
+                {
+                    popover.popdown();
+                }
+            });
+
+            self.results.append(&button);
+        }
+
+        if count == 0 {
+            let label = gtk::Label::new(Some(&tr("search-no-results")));
+            label.add_css_class("calendar-search__empty");
+
+            self.results.append(&label);
+        }
+    }
+}
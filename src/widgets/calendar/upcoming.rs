@@ -1,13 +1,21 @@
+use std::collections::HashSet;
+
 use chrono::{Days, TimeDelta};
 
 use crate::calendar::{Calendar, Manager};
-use crate::config::{Config, UpcomingFilter, UuidFilter};
+use crate::config::{
+    Config, Conflicts, UpcomingFilter, UpcomingRange, UpcomingSection, UuidFilter,
+};
+use crate::i18n::{self, tr, tr_args};
 use crate::widgets::calendar::Dates;
 use crate::{calendar, prelude::*};
 
 pub struct UpcomingWidget {
     config: Option<UpcomingFilter>,
     filter: Option<UuidFilter>,
+    /// Configured sections, the default ones depend on the day
+    sections: Option<Vec<UpcomingSection>>,
+    conflicts: Option<Conflicts>,
     wrapper: gtk::Box,
     grid: Option<gtk::Grid>,
 }
@@ -23,9 +31,21 @@ impl UpcomingWidget {
             include: config.include.clone(),
         });
 
+        let sections = config
+            .calendar
+            .upcomming
+            .as_ref()
+            .map(|config| config.sections.clone())
+            .filter(|sections| !sections.is_empty());
+
         Self {
             filter,
             config: config.calendar.upcomming.clone(),
+            sections,
+            conflicts: config
+                .conflicts
+                .clone()
+                .filter(|conflicts| conflicts.upcoming),
             wrapper,
             grid: None,
         }
@@ -35,10 +55,12 @@ impl UpcomingWidget {
         &self.wrapper
     }
 
-    fn should_skip(&self, uid: &uuid::Uuid) -> bool {
-        self.config
-            .as_ref()
-            .is_some_and(|c| c.skip_oneliner.contains(uid))
+    fn should_skip(&self, calendar: &Calendar) -> bool {
+        self.config.as_ref().is_some_and(|c| {
+            c.skip_oneliner
+                .iter()
+                .any(|matcher| matcher.matches(&calendar.uid, &calendar.name))
+        })
     }
 
     pub fn update_calendar(&mut self, manager: &Manager, dates: &Dates) {
@@ -57,42 +79,30 @@ impl UpcomingWidget {
 
         let mut grid_row = 0;
 
-        if let Some(events) = self.map_events(manager, now, now, now) {
-            let label = Self::create_name("Heute");
-            grid.attach(&label, 0, grid_row, 1, 1);
-            grid.attach(&events, 1, grid_row, 1, 1);
-            grid_row += 1;
-        }
+        let sections = self
+            .sections
+            .clone()
+            .unwrap_or_else(|| default_sections(now));
 
-        if let Some(events) = self.map_events(manager, now, now + Days::new(1), now + Days::new(1))
-        {
-            let label = Self::create_name("Morgen");
-            grid.attach(&label, 0, grid_row, 1, 1);
-            grid.attach(&events, 1, grid_row, 1, 1);
-            grid_row += 1;
-        }
+        for section in &sections {
+            let Some((from, to)) = section_dates(section.range, now, dates.first_weekday) else {
+                continue;
+            };
 
-        if now.weekday() == Weekday::Fri
-            && let Some(events) =
-                self.map_events(manager, now, now + Days::new(2), now + Days::new(2))
-        {
-            let label = Self::create_name("Sonntag");
-            grid.attach(&label, 0, grid_row, 1, 1);
-            grid.attach(&events, 1, grid_row, 1, 1);
-        } else if !matches!(now.weekday(), Weekday::Sat | Weekday::Sun) {
-            let num = u64::from(now.weekday().num_days_from_monday());
-            if let Some(events) = self.map_events(
-                manager,
-                now,
-                now + Days::new(5 - num),
-                now + Days::new(6 - num),
-            ) {
-                let label = Self::create_name("Nächstes Wochenende");
+            if let Some(events) = self.map_events(manager, section, now, from, to) {
+                let label = Self::create_name(&section.label);
                 grid.attach(&label, 0, grid_row, 1, 1);
                 grid.attach(&events, 1, grid_row, 1, 1);
+                grid_row += 1;
             }
         }
 
+        if let Some(conflicts) = self.map_conflicts(manager, now) {
+            let label = Self::create_name(&tr("upcoming-conflicts"));
+            grid.attach(&label, 0, grid_row, 1, 1);
+            grid.attach(&conflicts, 1, grid_row, 1, 1);
+        }
+
         self.wrapper.append(&grid);
 
         self.grid = Some(grid);
@@ -109,22 +119,84 @@ impl UpcomingWidget {
     fn map_events<'a>(
         &'a self,
         manager: &'a Manager,
+        section: &'a UpcomingSection,
         now: NaiveDate,
         from: NaiveDate,
         to: NaiveDate,
     ) -> Option<gtk::Box> {
+        let filter = section.filter.as_ref().or(self.filter.as_ref());
+
+        // Multi-day occurrences are listed on their first day in the range only
+        let mut seen = HashSet::new();
+
         let events = manager
-            .events_between(from, to, self.filter.as_ref())
-            .map(|item| self.create_event(item, now))
+            .events_between(from, to, filter)
+            .filter(|(_, date_time, event)| {
+                let start = manager.occurrence_start(&event.uid, date_time);
+
+                seen.insert((event.uid, start.copied()))
+            })
             .collect::<Vec<_>>();
 
         if events.is_empty() {
             return None;
         }
 
+        let max_items = section.max_items.unwrap_or(usize::MAX);
+
+        let wrapper = gtk::Box::new(gtk::Orientation::Vertical, 0);
+        for item in events.iter().take(max_items) {
+            wrapper.append(&self.create_event(*item, now));
+        }
+
+        if events.len() > max_items {
+            let more = gtk::Label::new(Some(&tr_args(
+                "upcoming-more",
+                &[("count", (events.len() - max_items).into())],
+            )));
+            more.add_css_class("calendar-upcoming__more");
+            more.set_halign(gtk::Align::Start);
+
+            wrapper.append(&more);
+        }
+
+        Some(wrapper)
+    }
+
+    fn map_conflicts(&self, manager: &Manager, now: NaiveDate) -> Option<gtk::Box> {
+        let config = self.conflicts.as_ref()?;
+        let conflicts = manager.conflicts_between(
+            now,
+            now + Days::new(u64::from(config.days)),
+            Some(&config.calendars),
+        );
+
+        if conflicts.is_empty() {
+            return None;
+        }
+
         let wrapper = gtk::Box::new(gtk::Orientation::Vertical, 0);
-        for event in events {
-            wrapper.append(&event);
+        for conflict in conflicts {
+            let time = conflict
+                .start
+                .and_utc()
+                .with_timezone(&Local)
+                .format_localized(&tr("format-conflict"), i18n::locale())
+                .to_string();
+
+            let label = gtk::Label::new(Some(&tr_args(
+                "upcoming-conflict",
+                &[
+                    ("first", conflict.first.2.summary.as_str().into()),
+                    ("second", conflict.second.2.summary.as_str().into()),
+                    ("time", time.into()),
+                ],
+            )));
+            label.add_css_class("calendar-upcoming__item");
+            label.add_css_class("calendar-upcoming__conflict");
+            label.set_halign(gtk::Align::Start);
+
+            wrapper.append(&label);
         }
 
         Some(wrapper)
@@ -143,7 +215,7 @@ impl UpcomingWidget {
         indicator.set_width_request(12);
         indicator.inline_css(&format!("background-color: {}", calendar.css_color()));
 
-        let text = if self.should_skip(&calendar.uid) {
+        let text = if self.should_skip(calendar) {
             event.summary.clone()
         } else {
             self.oneliner(event, now)
@@ -174,7 +246,7 @@ impl UpcomingWidget {
         if event.start_date() != now {
             info.push_str(
                 &start
-                    .format_localized("%a", chrono::Locale::de_DE)
+                    .format_localized(&tr("format-weekday"), i18n::locale())
                     .to_string(),
             );
         }
@@ -192,7 +264,7 @@ impl UpcomingWidget {
         {
             info.push(' ');
             info.push_str(
-                &end.format_localized("%a", chrono::Locale::de_DE)
+                &end.format_localized(&tr("format-weekday"), i18n::locale())
                     .to_string(),
             );
         }
@@ -211,3 +283,65 @@ impl UpcomingWidget {
         }
     }
 }
+
+/// Today, tomorrow and the weekend after tomorrow, which is only Sunday on Fridays
+fn default_sections(today: NaiveDate) -> Vec<UpcomingSection> {
+    let mut sections = vec![
+        ("upcoming-today", UpcomingRange::Day(0)),
+        ("upcoming-tomorrow", UpcomingRange::Day(1)),
+    ];
+
+    match today.weekday() {
+        Weekday::Fri => sections.push(("upcoming-sunday", UpcomingRange::Day(2))),
+        Weekday::Sat | Weekday::Sun => (),
+        _ => sections.push(("upcoming-next-weekend", UpcomingRange::NextWeekend)),
+    }
+
+    sections
+        .into_iter()
+        .map(|(label, range)| UpcomingSection {
+            label: tr(label),
+            range,
+            filter: None,
+            max_items: None,
+        })
+        .collect()
+}
+
+/// First and last day of a section relative to today, [`None`] if the range is empty
+fn section_dates(
+    range: UpcomingRange,
+    today: NaiveDate,
+    first_weekday: Weekday,
+) -> Option<(NaiveDate, NaiveDate)> {
+    let tomorrow = today + Days::new(1);
+    let week_end = today
+        .iter_days()
+        .skip(1)
+        .find(|date| date.weekday() == first_weekday)?
+        - Days::new(1);
+
+    let (from, to) = match range {
+        UpcomingRange::Day(offset) => {
+            let date = today + Days::new(offset.into());
+            (date, date)
+        }
+        UpcomingRange::NextWeekend => {
+            let saturday = today
+                .iter_days()
+                .find(|date| date.weekday() == Weekday::Sat)?;
+            let sunday = saturday + Days::new(1);
+
+            // Weekend days up to tomorrow are covered by other sections
+            if sunday <= tomorrow {
+                return None;
+            }
+
+            (saturday.max(tomorrow + Days::new(1)), sunday)
+        }
+        UpcomingRange::RestOfWeek => (tomorrow, week_end),
+        UpcomingRange::NextDays(days) => (tomorrow, today + Days::new(days.into())),
+    };
+
+    (from <= to).then_some((from, to))
+}
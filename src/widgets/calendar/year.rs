@@ -0,0 +1,280 @@
+use std::cell::Cell;
+use std::collections::{BTreeMap, HashSet};
+use std::rc::Rc;
+
+use chrono::{Datelike, Duration, NaiveDate};
+
+use crate::calendar::{Calendar, Manager};
+use crate::config::{Config, UuidFilter};
+use crate::i18n::{self, tr};
+use crate::messaging::{self, CalendarMessage};
+use crate::prelude::*;
+use crate::widgets::calendar::month::start_grid_date;
+use crate::widgets::calendar::{Dates, swipe_gesture};
+
+// Number of events per day and calendar
+type DensityMap<'a> = BTreeMap<NaiveDate, BTreeMap<uuid::Uuid, (usize, &'a Calendar)>>;
+
+pub struct YearWidget {
+    filter: Option<UuidFilter>,
+
+    wrapper: gtk::Box,
+    label: gtk::Label,
+    months: Vec<MiniMonth>,
+}
+
+struct MiniMonth {
+    wrapper: gtk::Box,
+    label: gtk::Label,
+    first: Rc<Cell<NaiveDate>>,
+    /// Day labels with the provider of their density color, reused on every update
+    days: Vec<(gtk::Label, gtk::CssProvider)>,
+}
+
+impl YearWidget {
+    pub fn new(config: &Config, dates: &Dates) -> Self {
+        let prev_button = gtk::Button::new();
+        prev_button.add_css_class("calendar-year__control");
+        prev_button.set_label("◀");
+        prev_button.set_height_request(44);
+        prev_button.set_width_request(44);
+        prev_button.connect_clicked(|_| messaging::send_message(CalendarMessage::YearPrev));
+
+        let next_button = gtk::Button::new();
+        next_button.add_css_class("calendar-year__control");
+        next_button.set_label("▶");
+        next_button.set_height_request(44);
+        next_button.set_width_request(44);
+        next_button.connect_clicked(|_| messaging::send_message(CalendarMessage::YearNext));
+
+        let label = gtk::Label::new(None);
+        label.add_css_class("calendar-year__label");
+
+        let control_wrapper = gtk::CenterBox::new();
+        control_wrapper.set_orientation(gtk::Orientation::Horizontal);
+        control_wrapper.set_hexpand(true);
+        control_wrapper.set_start_widget(Some(&prev_button));
+        control_wrapper.set_center_widget(Some(&label));
+        control_wrapper.set_end_widget(Some(&next_button));
+
+        let year_grid = gtk::Grid::builder()
+            .hexpand(true)
+            .vexpand(true)
+            .column_homogeneous(true)
+            .row_homogeneous(true)
+            .row_spacing(16)
+            .column_spacing(16)
+            .css_classes(["calendar-year__grid"])
+            .build();
+
+        let first = dates.selected.with_day(1).unwrap().with_month(1).unwrap();
+
+        let mut months = Vec::with_capacity(12);
+        for month_idx in 0..12 {
+            let month = MiniMonth::new(first + chrono::Months::new(month_idx), dates.first_weekday);
+
+            year_grid.attach(
+                month.widget(),
+                (month_idx % 4) as i32,
+                (month_idx / 4) as i32,
+                1,
+                1,
+            );
+
+            months.push(month);
+        }
+
+        let wrapper = gtk::Box::new(gtk::Orientation::Vertical, 16);
+        wrapper.add_css_class("calendar-year");
+        wrapper.set_expand(true);
+        wrapper.append(&control_wrapper);
+        wrapper.append(&year_grid);
+        wrapper.add_controller(swipe_gesture(
+            || CalendarMessage::YearPrev,
+            || CalendarMessage::YearNext,
+        ));
+        wrapper.connect_map(|_| messaging::send_message(CalendarMessage::YearShown));
+
+        Self {
+            filter: config.calendar.year.clone(),
+            wrapper,
+            label,
+            months,
+        }
+    }
+
+    pub const fn widget(&self) -> &gtk::Box {
+        &self.wrapper
+    }
+
+    /// Skipped while the page is hidden, it is updated once it is shown again
+    pub fn update_calendar(&mut self, manager: &Manager, dates: &Dates) {
+        if !self.wrapper.is_mapped() {
+            return;
+        }
+
+        let year_start = NaiveDate::from_ymd_opt(dates.selected.year(), 1, 1).unwrap();
+        let year_end = NaiveDate::from_ymd_opt(dates.selected.year(), 12, 31).unwrap();
+
+        self.label.set_label(&dates.selected.year().to_string());
+
+        // Events are counted once on every day they cover
+        let mut seen = HashSet::new();
+
+        let density = manager
+            .events_between(year_start, year_end, self.filter.as_ref())
+            .filter(|(_, date_time, event)| seen.insert((date_time.date(), event.uid)))
+            .fold(DensityMap::new(), |mut map, (calendar, date_time, _)| {
+                map.entry(date_time.date())
+                    .or_default()
+                    .entry(calendar.uid)
+                    .or_insert((0, calendar))
+                    .0 += 1;
+
+                map
+            });
+
+        let max = density
+            .values()
+            .map(|calendars| calendars.values().map(|(count, _)| count).sum::<usize>())
+            .max()
+            .unwrap_or(1);
+
+        for (month_idx, month) in self.months.iter().enumerate() {
+            month.update_calendar(
+                year_start + chrono::Months::new(month_idx as u32),
+                &density,
+                max,
+                dates,
+            );
+        }
+    }
+}
+
+impl MiniMonth {
+    fn new(first: NaiveDate, first_weekday: chrono::Weekday) -> Self {
+        let label = gtk::Label::new(None);
+        label.add_css_class("calendar-year__month__label");
+        label.set_halign(gtk::Align::Start);
+
+        let grid = gtk::Grid::builder()
+            .column_homogeneous(true)
+            .row_homogeneous(true)
+            .row_spacing(2)
+            .column_spacing(2)
+            .build();
+
+        for (column_idx, day) in i18n::weekday_labels(first_weekday).iter().enumerate() {
+            let weekday = gtk::Label::new(Some(day));
+            weekday.add_css_class("calendar-year__weekday-label");
+
+            grid.attach(&weekday, column_idx as i32, 0, 1, 1);
+        }
+
+        let first = Rc::new(Cell::new(first));
+
+        let mut days = Vec::with_capacity(42);
+        for idx in 0..42 {
+            let day = gtk::Label::new(None);
+            day.add_css_class("calendar-year__day");
+
+            let clickable = gtk::GestureClick::new();
+            clickable.connect_pressed(gtk::glib::clone!(
+                #[strong]
+                first,
+                move |controller, _, _, _| {
+                    if controller.current_button() != gtk::gdk::BUTTON_PRIMARY {
+                        return;
+                    }
+
+                    let date =
+                        start_grid_date(first.get(), first_weekday) + Duration::days(idx as i64);
+
+                    if date.month() == first.get().month() {
+                        messaging::send_message(CalendarMessage::SelectDate(date));
+
+                        // Jump back to the calendar page the date is shown on
+                        if let Some(stack) = controller
+                            .widget()
+                            .and_then(|widget| widget.ancestor(gtk::Stack::static_type()))
+                            .and_downcast::<gtk::Stack>()
+                        {
+                            stack.set_visible_child_name("calendar");
+                        }
+                    }
+                }
+            ));
+            day.add_controller(clickable);
+
+            let style = gtk::CssProvider::new();
+            #[allow(deprecated)]
+            day.style_context()
+                .add_provider(&style, gtk::STYLE_PROVIDER_PRIORITY_USER + 1);
+
+            grid.attach(&day, (idx % 7) as i32, (idx / 7) as i32 + 1, 1, 1);
+
+            days.push((day, style));
+        }
+
+        let wrapper = gtk::Box::new(gtk::Orientation::Vertical, 4);
+        wrapper.add_css_class("calendar-year__month");
+        wrapper.append(&label);
+        wrapper.append(&grid);
+
+        Self {
+            wrapper,
+            label,
+            first,
+            days,
+        }
+    }
+
+    const fn widget(&self) -> &gtk::Box {
+        &self.wrapper
+    }
+
+    fn update_calendar(&self, first: NaiveDate, density: &DensityMap, max: usize, dates: &Dates) {
+        self.first.set(first);
+        self.label.set_label(
+            &first
+                .format_localized(&tr("format-month"), i18n::locale())
+                .to_string(),
+        );
+
+        let grid_start = start_grid_date(first, dates.first_weekday);
+
+        for (idx, (day, style)) in self.days.iter().enumerate() {
+            let date = grid_start + Duration::days(idx as i64);
+
+            if date.month() != first.month() {
+                day.set_label("");
+                day.set_class_active("today", false);
+                day.set_class_active("selected", false);
+                style.load_from_string("");
+                continue;
+            }
+
+            day.set_label(&date.day().to_string());
+            day.set_class_active("today", dates.is_today(date));
+            day.set_class_active("selected", dates.is_selected(date));
+
+            let Some(calendars) = density.get(&date) else {
+                style.load_from_string("");
+                continue;
+            };
+
+            let count: usize = calendars.values().map(|(count, _)| count).sum();
+            let (_, calendar) = calendars
+                .values()
+                .max_by_key(|(count, _)| *count)
+                .expect("Density map has no empty days");
+
+            let alpha = (count as f32 / max as f32).mul_add(0.75, 0.25);
+
+            style.load_from_string(&format!(
+                "* {{ background-color: rgba({}, {}, {}, {alpha:.2}); }}",
+                calendar.color.red, calendar.color.green, calendar.color.blue
+            ));
+        }
+    }
+}
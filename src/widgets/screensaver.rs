@@ -6,10 +6,13 @@ use gtk::glib::Priority;
 
 use crate::config;
 use crate::config::Config;
+use crate::i18n::{self, tr};
 use crate::messaging;
-use crate::messaging::ScreensaverMessage;
+use crate::messaging::{ScreensaverMessage, VideoMessage};
 use crate::prelude::*;
+use crate::widgets::calendar::countdown::CountdownWidget;
 use crate::widgets::calendar::upcoming::UpcomingWidget;
+use crate::widgets::video::source;
 
 pub struct ScreensaverWidget {
     now: DateTime<Local>,
@@ -20,10 +23,11 @@ pub struct ScreensaverWidget {
     center_wrapper: gtk::Box,
     date: gtk::Label,
     time: gtk::Label,
+    pip: Option<clapper::Player>,
 }
 
 impl ScreensaverWidget {
-    pub fn new(config: &Config, upcoming: &UpcomingWidget) -> Self {
+    pub fn new(config: &Config, upcoming: &UpcomingWidget, countdown: &CountdownWidget) -> Self {
         let date = gtk::Label::builder()
             .css_classes(["screensaver__date"])
             .build();
@@ -53,6 +57,7 @@ impl ScreensaverWidget {
 
         center_wrapper.append(&clock);
         center_wrapper.append(upcoming.widget());
+        center_wrapper.append(countdown.widget());
 
         let controller = gtk::GestureClick::new();
         controller.connect_pressed(move |controller, _, _, _| {
@@ -72,6 +77,33 @@ impl ScreensaverWidget {
         wrapper.append(&center_wrapper);
         wrapper.add_controller(controller);
 
+        let pip = config.screensaver.pip.as_ref().and_then(|name| {
+            let Some(video) = config.videos.iter().find(|video| &video.name == name) else {
+                log::error!("Screensaver: unknown picture-in-picture video \"{name}\"");
+                return None;
+            };
+
+            let video_player = clapper_gtk::Video::new();
+            video_player.add_css_class("screensaver__pip");
+            video_player.set_halign(gtk::Align::End);
+            video_player.set_valign(gtk::Align::End);
+            video_player.set_size_request(480, 270);
+            video_player.set_can_target(false);
+
+            let player = video_player.player().unwrap();
+            player.set_audio_enabled(false);
+            player.set_autoplay(false);
+            player.set_subtitles_enabled(false);
+
+            let queue = player.queue().unwrap();
+            queue.add_item(&source::media_item(video));
+            queue.set_current_index(0);
+
+            wrapper.append(&video_player);
+
+            Some(player)
+        });
+
         gtk::glib::timeout_add_local_full(Duration::from_secs(1), Priority::DEFAULT_IDLE, move || {
             messaging::send_message(ScreensaverMessage::Tick);
 
@@ -86,6 +118,7 @@ impl ScreensaverWidget {
             center_wrapper,
             date,
             time,
+            pip,
         }
     }
 
@@ -124,6 +157,16 @@ impl ScreensaverWidget {
 
                 if activate != self.wrapper.is_visible() {
                     log::info!("Screensaver: {}", if activate { "activated" } else { "deactivated" });
+
+                    messaging::send_message(VideoMessage::ScreensaverActive(activate));
+
+                    if let Some(pip) = &self.pip {
+                        if activate {
+                            pip.play();
+                        } else {
+                            pip.stop();
+                        }
+                    }
                 }
 
                 self.wrapper.set_visible(activate);
@@ -133,7 +176,7 @@ impl ScreensaverWidget {
                 self.date.set_label(
                     &self
                         .now
-                        .format_localized("%d. %B", chrono::Locale::de_DE)
+                        .format_localized(&tr("format-screensaver-date"), i18n::locale())
                         .to_string(),
                 );
             }
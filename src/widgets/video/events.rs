@@ -0,0 +1,70 @@
+use std::collections::VecDeque;
+
+use crate::i18n::tr;
+use crate::messaging;
+use crate::messaging::VideoMessage;
+use crate::prelude::*;
+
+/// Most recent motion events next to the player, newest first
+pub struct EventHistory {
+    wrapper: gtk::Box,
+    list: gtk::Box,
+    entries: VecDeque<gtk::Button>,
+    size: usize,
+}
+
+impl EventHistory {
+    pub fn new(size: usize) -> Self {
+        let title = gtk::Label::new(Some(&tr("video-events")));
+        title.add_css_class("video-events__title");
+        title.set_halign(gtk::Align::Start);
+
+        let list = gtk::Box::new(gtk::Orientation::Vertical, 4);
+        list.add_css_class("video-events__list");
+
+        let scrolled = gtk::ScrolledWindow::new();
+        scrolled.set_vexpand(true);
+        scrolled.set_policy(gtk::PolicyType::Never, gtk::PolicyType::Automatic);
+        scrolled.set_child(Some(&list));
+
+        let wrapper = gtk::Box::new(gtk::Orientation::Vertical, 8);
+        wrapper.add_css_class("video-events");
+        wrapper.set_width_request(220);
+        wrapper.append(&title);
+        wrapper.append(&scrolled);
+
+        Self {
+            wrapper,
+            list,
+            entries: VecDeque::with_capacity(size),
+            size,
+        }
+    }
+
+    pub const fn widget(&self) -> &gtk::Box {
+        &self.wrapper
+    }
+
+    /// Adds an entry which shows the stream of the event when tapped
+    pub fn push(&mut self, idx: usize, text: &str) {
+        let label = gtk::Label::new(Some(text));
+        label.set_halign(gtk::Align::Start);
+        label.set_wrap(true);
+
+        let button = gtk::Button::new();
+        button.add_css_class("video-events__entry");
+        button.set_child(Some(&label));
+        button.connect_clicked(move |_| {
+            messaging::send_message(VideoMessage::VideoSelectIndex(Some(idx)));
+        });
+
+        self.list.prepend(&button);
+        self.entries.push_front(button);
+
+        while self.entries.len() > self.size {
+            if let Some(entry) = self.entries.pop_back() {
+                self.list.remove(&entry);
+            }
+        }
+    }
+}
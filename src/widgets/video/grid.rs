@@ -0,0 +1,156 @@
+use clapper::Player;
+
+use crate::config::Config;
+use crate::messaging::{self, VideoMessage};
+use crate::prelude::*;
+use crate::widgets::video::create_offline_placeholder;
+use crate::widgets::video::health::{Health, StreamHealth};
+use crate::widgets::video::source;
+
+/// All streams at once, one player per stream
+pub struct VideoGrid {
+    grid: gtk::Grid,
+    tiles: Vec<Tile>,
+}
+
+struct Tile {
+    name: String,
+    player: Player,
+    tile: gtk::Overlay,
+    offline: gtk::Box,
+    motion: gtk::Label,
+    health: StreamHealth,
+}
+
+impl VideoGrid {
+    pub fn new(config: &Config) -> Self {
+        let columns = config
+            .video_grid
+            .as_ref()
+            .map_or(2, |grid| grid.columns.max(1));
+
+        let grid = gtk::Grid::new();
+        grid.add_css_class("video-grid");
+        grid.set_expand(true);
+        grid.set_row_homogeneous(true);
+        grid.set_column_homogeneous(true);
+        grid.set_row_spacing(4);
+        grid.set_column_spacing(4);
+
+        let mut tiles = Vec::with_capacity(config.videos.len());
+        for (idx, video) in config.videos.iter().enumerate() {
+            let video_player = clapper_gtk::Video::new();
+            video_player.add_css_class("clapper");
+            video_player.set_expand(true);
+
+            let player = video_player.player().unwrap();
+            player.set_audio_enabled(false);
+            player.set_autoplay(false);
+            player.set_subtitles_enabled(false);
+
+            let queue = player.queue().unwrap();
+            queue.add_item(&source::media_item(video));
+            queue.set_current_index(0);
+
+            let label = gtk::Label::new(Some(&video.name));
+            label.add_css_class("video-grid__label");
+            label.set_halign(gtk::Align::Start);
+            label.set_valign(gtk::Align::Start);
+            label.set_can_target(false);
+
+            // Tapping a tile shows its stream in full size, before clapper toggles its controls
+            let clickable = gtk::GestureClick::new();
+            clickable.set_propagation_phase(gtk::PropagationPhase::Capture);
+            clickable.connect_released(move |gesture, _, _, _| {
+                gesture.set_state(gtk::EventSequenceState::Claimed);
+                messaging::send_message(VideoMessage::VideoSelectIndex(Some(idx)));
+            });
+
+            let offline = create_offline_placeholder();
+
+            let motion = gtk::Label::new(None);
+            motion.add_css_class("video-grid__motion");
+            motion.set_halign(gtk::Align::Start);
+            motion.set_valign(gtk::Align::End);
+            motion.set_can_target(false);
+            motion.set_visible(false);
+
+            let tile = gtk::Overlay::new();
+            tile.add_css_class("video-grid__tile");
+            tile.set_child(Some(&video_player));
+            tile.add_overlay(&offline);
+            tile.add_overlay(&label);
+            tile.add_overlay(&motion);
+            tile.add_controller(clickable);
+
+            let (column, row) = (idx as u16 % columns, idx as u16 / columns);
+            grid.attach(
+                &tile,
+                video.column.unwrap_or(column).into(),
+                video.row.unwrap_or(row).into(),
+                video.width.unwrap_or(1).into(),
+                video.height.unwrap_or(1).into(),
+            );
+
+            tiles.push(Tile {
+                name: video.name.clone(),
+                player,
+                tile,
+                offline,
+                motion,
+                health: StreamHealth::new(),
+            });
+        }
+
+        Self { grid, tiles }
+    }
+
+    pub const fn widget(&self) -> &gtk::Grid {
+        &self.grid
+    }
+
+    pub fn play(&mut self) {
+        for tile in &mut self.tiles {
+            tile.health.reset();
+            tile.offline.set_visible(false);
+            tile.player.play();
+        }
+    }
+
+    pub fn stop(&self) {
+        for tile in &self.tiles {
+            tile.player.stop();
+        }
+    }
+
+    /// Marks the tile of a stream with its latest motion event, `None` clears it
+    pub fn highlight(&self, idx: usize, text: Option<&str>) {
+        let Some(tile) = self.tiles.get(idx) else {
+            return;
+        };
+
+        tile.tile
+            .set_class_active("video-grid__tile--motion", text.is_some());
+        tile.motion.set_label(text.unwrap_or_default());
+        tile.motion.set_visible(text.is_some());
+    }
+
+    /// Reconnect streams which stopped or stalled, backing off for dead cameras
+    pub fn check(&mut self) {
+        for tile in &mut self.tiles {
+            match tile
+                .health
+                .check(tile.player.state(), tile.player.position())
+            {
+                Health::Healthy => tile.offline.set_visible(false),
+                Health::Offline => tile.offline.set_visible(true),
+                Health::Retry => {
+                    log::info!("Video grid: reconnecting \"{}\"", tile.name);
+
+                    tile.player.stop();
+                    tile.player.play();
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,193 @@
+use std::time::{Duration, Instant};
+
+use clapper::PlayerState;
+
+/// Time without new frames after which a playing stream counts as stalled
+const STALL_TIMEOUT: Duration = Duration::from_secs(15);
+const MIN_BACKOFF: Duration = Duration::from_secs(3);
+const MAX_BACKOFF: Duration = Duration::from_secs(5 * 60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Health {
+    Healthy,
+    /// Offline and waiting for the next retry
+    Offline,
+    /// Offline and due for a reconnect
+    Retry,
+}
+
+/// Stall detection and reconnect backoff of a single stream
+#[derive(Debug)]
+pub struct StreamHealth {
+    position: f64,
+    progress_at: Instant,
+    failures: u32,
+    retry_at: Option<Instant>,
+}
+
+impl StreamHealth {
+    pub fn new() -> Self {
+        Self {
+            position: 0.0,
+            progress_at: Instant::now(),
+            failures: 0,
+            retry_at: None,
+        }
+    }
+
+    /// Forget previous failures, e.g. when the stream was selected manually
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    /// Check the stream progress, to be called periodically.
+    pub fn check(&mut self, state: PlayerState, position: f64) -> Health {
+        self.check_at(state, position, Instant::now())
+    }
+
+    fn check_at(&mut self, state: PlayerState, position: f64, now: Instant) -> Health {
+        #[allow(clippy::float_cmp)]
+        let advanced = position != self.position;
+        if advanced {
+            self.position = position;
+            self.progress_at = now;
+        }
+
+        let healthy = matches!(state, PlayerState::Playing | PlayerState::Buffering)
+            && now.duration_since(self.progress_at) < STALL_TIMEOUT;
+
+        if healthy {
+            // A pending retry is dropped, so the next stall waits for a fresh backoff
+            self.retry_at = None;
+
+            // Only frames prove the camera is back, a connection alone is not enough
+            if advanced {
+                self.failures = 0;
+            }
+
+            return Health::Healthy;
+        }
+
+        match self.retry_at {
+            None => {
+                self.retry_at = Some(now + self.backoff());
+                self.failures = self.failures.saturating_add(1);
+
+                Health::Offline
+            }
+            Some(retry_at) if now >= retry_at => {
+                self.retry_at = None;
+                // Give the reconnect time to deliver frames
+                self.progress_at = now;
+
+                Health::Retry
+            }
+            Some(_) => Health::Offline,
+        }
+    }
+
+    fn backoff(&self) -> Duration {
+        MIN_BACKOFF
+            .saturating_mul(2_u32.saturating_pow(self.failures))
+            .min(MAX_BACKOFF)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use PlayerState::{Playing, Stopped};
+
+    const SECOND: Duration = Duration::from_secs(1);
+
+    #[test]
+    fn backoff_grows() {
+        let start = Instant::now();
+        let mut health = StreamHealth::new();
+
+        assert_eq!(health.check_at(Stopped, 0.0, start), Health::Offline);
+        assert_eq!(health.retry_at, Some(start + MIN_BACKOFF));
+
+        let mut now = start;
+        for backoff in [3, 6, 12, 24] {
+            now += Duration::from_secs(backoff);
+
+            assert_eq!(health.check_at(Stopped, 0.0, now - SECOND), Health::Offline);
+            assert_eq!(health.check_at(Stopped, 0.0, now), Health::Retry);
+            assert_eq!(health.check_at(Stopped, 0.0, now), Health::Offline);
+        }
+
+        assert_eq!(health.retry_at, Some(now + Duration::from_secs(48)));
+    }
+
+    #[test]
+    fn backoff_is_capped() {
+        let mut health = StreamHealth::new();
+
+        health.failures = 6;
+        assert_eq!(health.backoff(), Duration::from_secs(192));
+
+        health.failures = 7;
+        assert_eq!(health.backoff(), MAX_BACKOFF);
+
+        health.failures = u32::MAX;
+        assert_eq!(health.backoff(), MAX_BACKOFF);
+    }
+
+    #[test]
+    fn recovers_on_frames() {
+        let start = Instant::now();
+        let mut health = StreamHealth::new();
+
+        health.check_at(Stopped, 0.0, start);
+        health.check_at(Stopped, 0.0, start + MIN_BACKOFF);
+        health.check_at(Stopped, 0.0, start + MIN_BACKOFF);
+        assert_eq!(health.failures, 2);
+
+        // Reconnected, but the failures are only forgotten once frames arrive
+        let now = start + MIN_BACKOFF * 2;
+        assert_eq!(health.check_at(Playing, 0.0, now), Health::Healthy);
+        assert_eq!(health.failures, 2);
+
+        assert_eq!(health.check_at(Playing, 1.0, now), Health::Healthy);
+        assert_eq!(health.failures, 0);
+        assert_eq!(health.retry_at, None);
+        assert_eq!(health.backoff(), MIN_BACKOFF);
+    }
+
+    #[test]
+    fn stalls_without_frames() {
+        let start = Instant::now();
+        let mut health = StreamHealth::new();
+
+        assert_eq!(health.check_at(Playing, 1.0, start), Health::Healthy);
+
+        let stalled = start + STALL_TIMEOUT;
+        assert_eq!(
+            health.check_at(Playing, 1.0, stalled - SECOND),
+            Health::Healthy
+        );
+        assert_eq!(health.check_at(Playing, 1.0, stalled), Health::Offline);
+    }
+
+    #[test]
+    fn second_stall_waits_for_backoff() {
+        let start = Instant::now();
+        let mut health = StreamHealth::new();
+
+        assert_eq!(health.check_at(Stopped, 0.0, start), Health::Offline);
+        assert_eq!(
+            health.check_at(Playing, 0.0, start + SECOND),
+            Health::Healthy
+        );
+
+        // The retry scheduled for the first stall is over, the second one backs off again
+        let stall = start + 4 * SECOND;
+        assert_eq!(health.check_at(Stopped, 0.0, stall), Health::Offline);
+        assert_eq!(health.retry_at, Some(stall + 2 * MIN_BACKOFF));
+        assert_eq!(
+            health.check_at(Stopped, 0.0, stall + 2 * MIN_BACKOFF),
+            Health::Retry
+        );
+    }
+}
@@ -0,0 +1,704 @@
+use std::time::Duration;
+
+use clapper::Player;
+use clapper::PlayerState;
+use clapper::Queue;
+use gtk::glib;
+use gtk::glib::Priority;
+
+use crate::config::{Config, VideoAudio};
+use crate::events::MotionEvent;
+use crate::i18n::tr;
+use crate::messaging;
+use crate::messaging::{ScreensaverMessage, VideoMessage};
+use crate::prelude::*;
+use crate::widgets::video::events::EventHistory;
+use crate::widgets::video::grid::VideoGrid;
+use crate::widgets::video::health::{Health, StreamHealth};
+use crate::widgets::video::ptz::PtzControls;
+use crate::widgets::video::snapshot::Snapshots;
+use crate::widgets::video::talk::Talk;
+use crate::widgets::video::tour::Tour;
+
+mod events;
+mod grid;
+mod health;
+mod ptz;
+mod snapshot;
+pub mod source;
+mod talk;
+mod tour;
+
+pub struct Video {
+    wrapper: gtk::Box,
+    stack: gtk::Stack,
+    grid: Option<VideoGrid>,
+    snapshots: Option<Snapshots>,
+
+    video_player: clapper_gtk::Video,
+    player: Player,
+    queue: Queue,
+    spinners: Vec<gtk::Spinner>,
+    offline: gtk::Box,
+    ptz: PtzControls,
+
+    audio: Vec<Option<VideoAudio>>,
+    mute_button: gtk::Button,
+    talk_button: gtk::Button,
+    muted: bool,
+    talk: Talk,
+
+    event_cameras: Vec<String>,
+    history: Option<EventHistory>,
+    highlight: u32,
+    motion: Vec<Option<(String, glib::SourceId)>>,
+    motion_label: gtk::Label,
+
+    tour: Option<Tour>,
+    tour_button: Option<gtk::Button>,
+
+    health: StreamHealth,
+    suspended: bool,
+}
+
+impl Video {
+    pub fn new(config: &Config) -> Self {
+        let video_player = clapper_gtk::Video::new();
+        video_player.set_visible(true);
+        video_player.add_css_class("clapper");
+
+        let player = video_player.player().unwrap();
+        player.set_audio_enabled(false);
+        player.set_autoplay(false);
+        player.set_subtitles_enabled(false);
+
+        let queue = player.queue().unwrap();
+        for video in &config.videos {
+            queue.add_item(&source::media_item(video));
+        }
+
+        let button_wrapper = gtk::Box::new(gtk::Orientation::Horizontal, 8);
+        button_wrapper.add_css_class("buttons");
+
+        let mut spinners = Vec::with_capacity(config.videos.len());
+        for (idx, video) in config.videos.iter().enumerate() {
+            let label = gtk::Label::new(Some(&video.name));
+            label.set_hexpand(false);
+            label.set_margin_vertical(2);
+
+            let spinner = gtk::Spinner::new();
+            spinner.set_visible(false);
+            spinner.set_spinning(false);
+
+            let spinner_wrapper = gtk::Box::new(gtk::Orientation::Horizontal, 0);
+            spinner_wrapper.set_expand(false);
+            spinner_wrapper.set_align(gtk::Align::Center);
+            spinner_wrapper.append(&spinner);
+
+            let content = gtk::Box::new(gtk::Orientation::Horizontal, 8);
+            content.set_halign(gtk::Align::Center);
+            content.set_hexpand(true);
+            content.append(&spinner_wrapper);
+            content.append(&label);
+
+            let button = gtk::Button::new();
+            button.set_hexpand(true);
+            button.set_child(Some(&content));
+            button.connect_clicked(move |_| {
+                messaging::send_message(VideoMessage::VideoSelectIndex(Some(idx)));
+            });
+
+            button_wrapper.append(&button);
+            spinners.push(spinner);
+        }
+
+        let mute_button = gtk::Button::with_label(&tr("video-mute"));
+        mute_button.set_hexpand(true);
+        mute_button.set_visible(false);
+        mute_button.connect_clicked(|_| messaging::send_message(VideoMessage::ToggleMute));
+
+        let talk_button = gtk::Button::with_label(&tr("video-talk"));
+        talk_button.set_hexpand(true);
+        talk_button.set_visible(false);
+        talk_button.add_controller(talk_gesture());
+
+        button_wrapper.append(&mute_button);
+        button_wrapper.append(&talk_button);
+
+        let stack = gtk::Stack::new();
+        stack.set_expand(true);
+        stack.set_transition_type(gtk::StackTransitionType::Crossfade);
+
+        let offline = create_offline_placeholder();
+
+        let single = gtk::Overlay::new();
+        single.set_child(Some(&video_player));
+        single.add_overlay(&offline);
+
+        let ptz = PtzControls::new(config);
+        single.add_overlay(ptz.widget());
+
+        let motion_label = gtk::Label::new(None);
+        motion_label.add_css_class("video-motion");
+        motion_label.set_halign(gtk::Align::Start);
+        motion_label.set_valign(gtk::Align::Start);
+        motion_label.set_can_target(false);
+        motion_label.set_visible(false);
+        single.add_overlay(&motion_label);
+
+        stack.add_named(&single, Some("single"));
+
+        let grid = config.video_grid.as_ref().map(|_| VideoGrid::new(config));
+
+        if let Some(grid) = &grid {
+            stack.add_named(grid.widget(), Some("grid"));
+
+            let button = gtk::Button::with_label(&tr("video-grid"));
+            button.set_hexpand(true);
+            button.connect_clicked(|_| messaging::send_message(VideoMessage::ShowGrid));
+
+            button_wrapper.append(&button);
+        }
+
+        let snapshots = config.snapshots.as_ref().map(Snapshots::new);
+
+        if let Some(snapshots) = &snapshots {
+            stack.add_named(snapshots.widget(), Some("gallery"));
+
+            let snapshot_button = gtk::Button::with_label(&tr("video-snapshot"));
+            snapshot_button.set_hexpand(true);
+            snapshot_button.connect_clicked(|_| messaging::send_message(VideoMessage::Snapshot));
+
+            let gallery_button = gtk::Button::with_label(&tr("video-gallery"));
+            gallery_button.set_hexpand(true);
+            gallery_button.connect_clicked(|_| messaging::send_message(VideoMessage::ShowGallery));
+
+            button_wrapper.append(&snapshot_button);
+            button_wrapper.append(&gallery_button);
+        }
+
+        let tour = Tour::new(config);
+
+        let tour_button = tour.as_ref().map(|tour| {
+            let button = gtk::Button::with_label(&tr("video-tour"));
+            button.set_hexpand(true);
+            button.set_class_active("video-tour--active", tour.is_enabled());
+            button.connect_clicked(|_| messaging::send_message(VideoMessage::ToggleTour));
+
+            button_wrapper.append(&button);
+            button
+        });
+
+        stack.set_visible_child_name("single");
+
+        let history = config
+            .events
+            .as_ref()
+            .map(|events| EventHistory::new(events.history));
+
+        let content = gtk::Box::new(gtk::Orientation::Horizontal, 0);
+        content.append(&stack);
+
+        if let Some(history) = &history {
+            content.append(history.widget());
+        }
+
+        let wrapper = gtk::Box::new(gtk::Orientation::Vertical, 0);
+        wrapper.add_css_class("video-player");
+        wrapper.append(&content);
+        wrapper.append(&button_wrapper);
+
+        if tour
+            .as_ref()
+            .is_some_and(|tour| tour.is_enabled() && tour.next(usize::MAX).is_some())
+        {
+            messaging::send_message(VideoMessage::TourNext);
+        } else if !config.videos.is_empty() {
+            messaging::send_message(VideoMessage::VideoSelectIndex(Some(0)));
+        }
+
+        glib::timeout_add_local_full(Duration::from_secs(5), Priority::DEFAULT_IDLE, || {
+            messaging::send_message(VideoMessage::CheckVideoState(None));
+
+            glib::ControlFlow::Continue
+        });
+
+        player.connect_state_notify(|player| {
+            messaging::send_message(VideoMessage::CheckVideoState(Some(player.state())))
+        });
+
+        Self {
+            wrapper,
+            stack,
+            grid,
+            snapshots,
+            video_player,
+            player,
+            queue,
+            spinners,
+            offline,
+            ptz,
+            audio: config
+                .videos
+                .iter()
+                .map(|video| video.audio.clone())
+                .collect(),
+            mute_button,
+            talk_button,
+            muted: false,
+            talk: Talk::default(),
+            event_cameras: config
+                .videos
+                .iter()
+                .map(|video| {
+                    video
+                        .event_camera
+                        .as_ref()
+                        .unwrap_or(&video.name)
+                        .to_lowercase()
+                })
+                .collect(),
+            history,
+            highlight: config.events.as_ref().map_or(0, |events| events.highlight),
+            motion: config.videos.iter().map(|_| None).collect(),
+            motion_label,
+            tour,
+            tour_button,
+            health: StreamHealth::new(),
+            suspended: false,
+        }
+    }
+
+    pub const fn widget(&self) -> &gtk::Box {
+        &self.wrapper
+    }
+
+    pub fn update(&mut self, message: VideoMessage) {
+        match message {
+            VideoMessage::CheckVideoState(_) if self.suspended || self.is_page("gallery") => (),
+            VideoMessage::CheckVideoState(_) if self.is_page("grid") => {
+                if let Some(grid) = &mut self.grid {
+                    grid.check();
+                }
+            }
+            VideoMessage::CheckVideoState(state) => {
+                let state = state.unwrap_or_else(|| self.player.state());
+
+                match self.health.check(state, self.player.position()) {
+                    Health::Healthy => {
+                        self.offline.set_visible(false);
+
+                        if state == PlayerState::Playing {
+                            self.stop_spinners();
+                        }
+                    }
+                    Health::Offline => {
+                        self.offline.set_visible(true);
+                        self.stop_spinners();
+                    }
+                    Health::Retry => {
+                        let idx = self.queue.current_index() as usize;
+                        log::info!("Video player: reconnecting \"{}\"", self.item_name(idx));
+
+                        self.player.stop();
+                        self.player.play();
+                    }
+                }
+            }
+            VideoMessage::VideoSelectIndex(clicked_idx) => {
+                let idx = self.select(clicked_idx);
+
+                // Selecting a stream by hand pauses the tour, resuming the shown one continues it
+                if let Some(tour) = &mut self.tour {
+                    if clicked_idx.is_some() {
+                        tour.pause();
+                    } else {
+                        tour.shown(idx);
+                    }
+                }
+            }
+            VideoMessage::TourNext => {
+                if self.suspended || !self.is_page("single") {
+                    return;
+                }
+
+                let current = self.queue.current_index() as usize;
+                let Some(idx) = self.tour.as_ref().and_then(|tour| tour.next(current)) else {
+                    return;
+                };
+
+                if idx != current {
+                    self.select(Some(idx));
+                }
+
+                if let Some(tour) = &mut self.tour {
+                    tour.shown(idx);
+                }
+            }
+            VideoMessage::ToggleTour => {
+                if let Some(tour) = &mut self.tour {
+                    tour.set_enabled(!tour.is_enabled());
+                    let state = if tour.is_enabled() {
+                        "started"
+                    } else {
+                        "stopped"
+                    };
+                    log::info!("Video player: tour {state}");
+
+                    if let Some(button) = &self.tour_button {
+                        button.set_class_active("video-tour--active", tour.is_enabled());
+                    }
+                }
+            }
+            VideoMessage::ShowStream {
+                name,
+                duration,
+                snapshot,
+            } => {
+                match self.item_index(&name) {
+                    Some(idx) => {
+                        log::info!("Video player: showing stream \"{name}\"");
+
+                        if let Some(tour) = &mut self.tour {
+                            tour.hold(duration);
+                        }
+
+                        // Resume right away instead of waiting for the screensaver to disappear
+                        self.suspended = false;
+
+                        messaging::send_message(ScreensaverMessage::Reset);
+                        messaging::send_message(VideoMessage::VideoSelectIndex(Some(idx)));
+                        messaging::send_message(VideoMessage::StreamShown {
+                            duration,
+                            grid: self.is_page("grid"),
+                        });
+
+                        if snapshot {
+                            // Give the stream some time to deliver frames
+                            glib::timeout_add_seconds_local_once(3, || {
+                                messaging::send_message(VideoMessage::Snapshot);
+                            });
+                        }
+                    }
+                    None => log::error!("Video player: unknown stream \"{name}\""),
+                }
+            }
+            // Handled by the app
+            VideoMessage::StreamShown { .. } => (),
+            VideoMessage::ScreensaverActive(active) if active != self.suspended => {
+                self.suspended = active;
+
+                if active {
+                    log::info!("Video player: stopping streams for the screensaver");
+
+                    self.stop_talking();
+                    self.player.stop();
+                    self.stop_spinners();
+
+                    if let Some(tour) = &mut self.tour {
+                        tour.stop();
+                    }
+
+                    if let Some(grid) = &self.grid {
+                        grid.stop();
+                    }
+                } else if self.is_page("grid") {
+                    messaging::send_message(VideoMessage::ShowGrid);
+                } else if self.is_page("gallery") {
+                    // Nothing was playing, the gallery stays as it was
+                } else {
+                    messaging::send_message(VideoMessage::VideoSelectIndex(None));
+                }
+            }
+            VideoMessage::ScreensaverActive(_) => (),
+            VideoMessage::ToggleMute => self.set_muted(!self.muted),
+            VideoMessage::Talk(true) => {
+                let idx = self.queue.current_index() as usize;
+                let command = match self.audio.get(idx) {
+                    Some(Some(audio)) if !audio.talk.is_empty() => audio.talk.clone(),
+                    _ => return,
+                };
+
+                if self.suspended || !self.is_page("single") {
+                    return;
+                }
+
+                log::info!("Video player: talking to \"{}\"", self.item_name(idx));
+
+                // Avoid feedback between the speaker and the microphone
+                self.player.set_mute(true);
+                self.talk.start(&command);
+                self.talk_button.add_css_class("video-talk--active");
+            }
+            VideoMessage::Talk(false) => self.stop_talking(),
+            VideoMessage::PtzConnected(idx, ptz) => self.ptz.connected(idx, ptz),
+            VideoMessage::PtzMove(direction) => self.ptz.move_camera(direction),
+            VideoMessage::PtzPreset(token) => self.ptz.goto_preset(token),
+            VideoMessage::Motion(event) => self.motion(&event),
+            VideoMessage::MotionExpired(idx) => {
+                if let Some(motion) = self.motion.get_mut(idx) {
+                    *motion = None;
+                }
+
+                if let Some(grid) = &self.grid {
+                    grid.highlight(idx, None);
+                }
+
+                self.show_motion(self.queue.current_index() as usize);
+            }
+            VideoMessage::Snapshot => {
+                let Some(snapshots) = &self.snapshots else {
+                    return;
+                };
+
+                if !self.is_page("single") {
+                    log::warn!("Video player: snapshots are only taken of a single stream");
+                    return;
+                }
+
+                let name = self.item_name(self.queue.current_index() as usize);
+
+                match snapshots.capture(&self.video_player, &self.player, &name) {
+                    Ok(path) => {
+                        log::info!("Video player: saved snapshot {}", path.display());
+
+                        snapshots.refresh();
+                    }
+                    Err(err) => log::error!("Video player: failed to save snapshot: {err}"),
+                }
+            }
+            VideoMessage::ShowGallery => {
+                let Some(snapshots) = &self.snapshots else {
+                    return;
+                };
+
+                snapshots.refresh();
+
+                self.apply_audio(None);
+                self.stop_spinners();
+                self.offline.set_visible(false);
+                self.player.stop();
+
+                if let Some(tour) = &mut self.tour {
+                    tour.stop();
+                }
+
+                if let Some(grid) = &self.grid {
+                    grid.stop();
+                }
+
+                self.stack.set_visible_child_name("gallery");
+            }
+            VideoMessage::ShowGrid => {
+                if self.grid.is_none() {
+                    return;
+                }
+
+                log::info!("Video player: showing grid");
+
+                if let Some(tour) = &mut self.tour {
+                    tour.stop();
+                }
+
+                self.apply_audio(None);
+                self.stop_spinners();
+                self.offline.set_visible(false);
+
+                self.player.stop();
+                self.stack.set_visible_child_name("grid");
+
+                if let Some(grid) = &mut self.grid {
+                    grid.play();
+                }
+            }
+        }
+    }
+
+    fn stop_spinners(&self) {
+        for spinner in &self.spinners {
+            if spinner.is_visible() {
+                spinner.stop();
+                spinner.set_visible(false);
+            }
+        }
+    }
+
+    /// Shows a stream in the single player, `None` restarts the current one
+    fn select(&mut self, clicked_idx: Option<usize>) -> usize {
+        self.health.reset();
+        self.offline.set_visible(false);
+
+        if self.is_page("grid")
+            && let Some(grid) = &self.grid
+        {
+            grid.stop();
+        }
+
+        self.stack.set_visible_child_name("single");
+
+        let clicked_idx = clicked_idx.unwrap_or_else(|| self.queue.current_index() as usize);
+        self.show_motion(clicked_idx);
+        log::info!(
+            "Video player: selecting video \"{}\"",
+            self.item_name(clicked_idx)
+        );
+
+        for (idx, spinner) in self.spinners.iter().enumerate() {
+            if idx == clicked_idx {
+                spinner.start();
+                spinner.set_visible(true);
+            } else {
+                spinner.stop();
+                spinner.set_visible(false);
+            }
+        }
+
+        self.apply_audio(Some(clicked_idx));
+        self.ptz.select(clicked_idx);
+
+        self.queue.select_item(None);
+        self.queue.set_current_index(clicked_idx as u32);
+        self.player.play();
+
+        clicked_idx
+    }
+
+    fn motion(&mut self, event: &MotionEvent) {
+        let camera = event.camera.to_lowercase();
+        let Some(idx) = self.event_cameras.iter().position(|name| *name == camera) else {
+            log::warn!(
+                "Video player: motion event of unknown camera \"{}\"",
+                event.camera
+            );
+            return;
+        };
+
+        let text = format!(
+            "{}, {}, {}",
+            capitalize(&event.label),
+            self.item_name(idx),
+            event.time.format("%H:%M")
+        );
+
+        if let Some(history) = &mut self.history {
+            history.push(idx, &text);
+        }
+
+        if let Some(grid) = &self.grid {
+            grid.highlight(idx, Some(&text));
+        }
+
+        let timeout = glib::timeout_add_seconds_local_once(self.highlight, move || {
+            messaging::send_message(VideoMessage::MotionExpired(idx));
+        });
+
+        if let Some((_, previous)) = self.motion[idx].replace((text, timeout)) {
+            remove_source(Some(previous));
+        }
+
+        self.show_motion(self.queue.current_index() as usize);
+    }
+
+    /// Labels the single stream with its latest motion event while it is highlighted
+    fn show_motion(&self, idx: usize) {
+        match self.motion.get(idx).and_then(Option::as_ref) {
+            Some((text, _)) => {
+                self.motion_label.set_label(text);
+                self.motion_label.set_visible(true);
+            }
+            None => self.motion_label.set_visible(false),
+        }
+    }
+
+    /// Applies the audio settings of a stream, `None` disables audio for the grid or gallery
+    fn apply_audio(&mut self, idx: Option<usize>) {
+        self.stop_talking();
+
+        let audio = idx
+            .and_then(|idx| self.audio.get(idx))
+            .and_then(Option::clone);
+
+        self.player.set_audio_enabled(audio.is_some());
+        self.mute_button.set_visible(audio.is_some());
+        self.talk_button
+            .set_visible(audio.as_ref().is_some_and(|audio| !audio.talk.is_empty()));
+
+        if let Some(audio) = audio {
+            self.player.set_volume(audio.volume);
+            self.set_muted(audio.muted);
+        }
+    }
+
+    fn set_muted(&mut self, muted: bool) {
+        self.muted = muted;
+        self.player.set_mute(muted);
+        self.mute_button
+            .set_label(&tr(if muted { "video-unmute" } else { "video-mute" }));
+    }
+
+    fn stop_talking(&mut self) {
+        if self.talk.stop() {
+            self.player.set_mute(self.muted);
+            self.talk_button.remove_css_class("video-talk--active");
+        }
+    }
+
+    fn is_page(&self, name: &str) -> bool {
+        self.stack.visible_child_name().as_deref() == Some(name)
+    }
+
+    fn item_index(&self, name: &str) -> Option<usize> {
+        (0..self.queue.n_items() as usize).find(|idx| self.item_name(*idx) == name)
+    }
+
+    fn item_name(&self, idx: usize) -> String {
+        self.queue
+            .item(idx as u32)
+            .and_then(|item| item.property_value("name").get::<String>().ok())
+            .unwrap_or_else(|| format!("index {}", idx))
+    }
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+/// Talks only while the button is held
+fn talk_gesture() -> gtk::GestureClick {
+    let gesture = gtk::GestureClick::new();
+    gesture.set_propagation_phase(gtk::PropagationPhase::Capture);
+
+    gesture.connect_pressed(|_, _, _, _| messaging::send_message(VideoMessage::Talk(true)));
+    gesture.connect_released(|_, _, _, _| messaging::send_message(VideoMessage::Talk(false)));
+    gesture.connect_cancel(|_, _| messaging::send_message(VideoMessage::Talk(false)));
+    // The button may be released outside of it, or the gesture reset without a release
+    gesture.connect_unpaired_release(|_, _, _, _, _| {
+        messaging::send_message(VideoMessage::Talk(false));
+    });
+    gesture.connect_stopped(|_| messaging::send_message(VideoMessage::Talk(false)));
+
+    gesture
+}
+
+/// Covers the last frame of a stream which stalled or went offline
+fn create_offline_placeholder() -> gtk::Box {
+    let label = gtk::Label::new(Some(&tr("video-offline")));
+    label.add_css_class("video-offline__label");
+
+    let placeholder = gtk::Box::new(gtk::Orientation::Vertical, 0);
+    placeholder.add_css_class("video-offline");
+    placeholder.set_valign(gtk::Align::Fill);
+    placeholder.set_halign(gtk::Align::Fill);
+    placeholder.set_visible(false);
+    placeholder.set_can_target(false);
+
+    label.set_vexpand(true);
+    placeholder.append(&label);
+
+    placeholder
+}
@@ -0,0 +1,233 @@
+use std::sync::mpsc;
+
+use crate::config::{Config, Onvif};
+use crate::messaging;
+use crate::messaging::VideoMessage;
+use crate::onvif::{self, Direction};
+use crate::prelude::*;
+
+/// Camera request run by the worker thread
+type Job = Box<dyn FnOnce() + Send>;
+
+enum State {
+    Disconnected,
+    Connecting,
+    Connected(onvif::Ptz),
+}
+
+/// Pan/tilt/zoom and preset buttons shown over streams of ONVIF PTZ cameras
+pub struct PtzControls {
+    wrapper: gtk::Box,
+    presets: gtk::Box,
+
+    configs: Vec<Option<Onvif>>,
+    states: Vec<State>,
+    selected: Option<usize>,
+    /// Moves, stops and presets are sent one after another, so a stop never overtakes its move
+    worker: Option<mpsc::Sender<Job>>,
+}
+
+impl PtzControls {
+    pub fn new(config: &Config) -> Self {
+        let grid = gtk::Grid::new();
+        grid.add_css_class("video-ptz__pad");
+        grid.set_row_spacing(4);
+        grid.set_column_spacing(4);
+
+        for (label, direction, column, row) in [
+            ("+", Direction::ZoomIn, 0, 0),
+            ("▲", Direction::Up, 1, 0),
+            ("−", Direction::ZoomOut, 2, 0),
+            ("◀", Direction::Left, 0, 1),
+            ("▶", Direction::Right, 2, 1),
+            ("▼", Direction::Down, 1, 2),
+        ] {
+            let button = gtk::Button::with_label(label);
+            button.add_css_class("video-ptz__control");
+            button.set_height_request(44);
+            button.set_width_request(44);
+            button.add_controller(hold_gesture(direction));
+
+            grid.attach(&button, column, row, 1, 1);
+        }
+
+        let presets = gtk::Box::new(gtk::Orientation::Vertical, 4);
+        presets.add_css_class("video-ptz__presets");
+
+        let wrapper = gtk::Box::new(gtk::Orientation::Vertical, 8);
+        wrapper.add_css_class("video-ptz");
+        wrapper.set_halign(gtk::Align::End);
+        wrapper.set_valign(gtk::Align::End);
+        wrapper.set_visible(false);
+        wrapper.append(&presets);
+        wrapper.append(&grid);
+
+        let configs: Vec<_> = config
+            .videos
+            .iter()
+            .map(|video| video.onvif.clone())
+            .collect();
+        let worker = configs.iter().any(Option::is_some).then(spawn_worker);
+
+        Self {
+            wrapper,
+            presets,
+            states: configs.iter().map(|_| State::Disconnected).collect(),
+            configs,
+            selected: None,
+            worker,
+        }
+    }
+
+    pub const fn widget(&self) -> &gtk::Box {
+        &self.wrapper
+    }
+
+    /// Shows the controls of the selected stream, connecting to its camera on first use
+    pub fn select(&mut self, idx: usize) {
+        self.selected = Some(idx);
+        self.update();
+
+        let (Some(Some(config)), Some(state)) = (self.configs.get(idx), self.states.get_mut(idx))
+        else {
+            return;
+        };
+
+        if !matches!(state, State::Disconnected) {
+            return;
+        }
+
+        *state = State::Connecting;
+
+        let client = onvif::Client::from(config);
+        let profile = config.profile.clone();
+
+        gtk::gio::spawn_blocking(move || {
+            let ptz = match client.ptz(profile.as_deref()) {
+                Ok(ptz) => {
+                    log::info!(
+                        "Video player: PTZ connected, {} presets",
+                        ptz.presets().len()
+                    );
+                    Some(Box::new(ptz))
+                }
+                Err(err) => {
+                    log::error!("Video player: failed to connect PTZ: {err}");
+                    None
+                }
+            };
+
+            messaging::send_message(VideoMessage::PtzConnected(idx, ptz));
+        });
+    }
+
+    /// Failed connections are retried on the next selection
+    pub fn connected(&mut self, idx: usize, ptz: Option<Box<onvif::Ptz>>) {
+        if let Some(state) = self.states.get_mut(idx) {
+            *state = ptz.map_or(State::Disconnected, |ptz| State::Connected(*ptz));
+        }
+
+        if self.selected == Some(idx) {
+            self.update();
+        }
+    }
+
+    /// Starts moving in a direction, `None` stops the camera
+    pub fn move_camera(&self, direction: Option<Direction>) {
+        let Some((ptz, speed)) = self.selected_ptz() else {
+            return;
+        };
+
+        self.run(move || {
+            let result = match direction {
+                Some(direction) => ptz.continuous_move(direction, speed),
+                None => ptz.stop(),
+            };
+
+            if let Err(err) = result {
+                log::error!("Video player: failed to move PTZ camera: {err}");
+            }
+        });
+    }
+
+    pub fn goto_preset(&self, token: String) {
+        let Some((ptz, _)) = self.selected_ptz() else {
+            return;
+        };
+
+        self.run(move || {
+            if let Err(err) = ptz.goto_preset(&token) {
+                log::error!("Video player: failed to go to PTZ preset \"{token}\": {err}");
+            }
+        });
+    }
+
+    fn run(&self, job: impl FnOnce() + Send + 'static) {
+        if let Some(worker) = &self.worker
+            && worker.send(Box::new(job)).is_err()
+        {
+            log::error!("Video player: PTZ worker stopped");
+        }
+    }
+
+    fn selected_ptz(&self) -> Option<(onvif::Ptz, f32)> {
+        let idx = self.selected?;
+
+        match (self.states.get(idx), self.configs.get(idx)) {
+            (Some(State::Connected(ptz)), Some(Some(config))) => Some((ptz.clone(), config.speed)),
+            _ => None,
+        }
+    }
+
+    fn update(&self) {
+        self.presets.remove_all();
+
+        let ptz = match self.selected.and_then(|idx| self.states.get(idx)) {
+            Some(State::Connected(ptz)) => ptz,
+            _ => {
+                self.wrapper.set_visible(false);
+                return;
+            }
+        };
+
+        for preset in ptz.presets() {
+            let token = preset.token.clone();
+
+            let button = gtk::Button::with_label(&preset.name);
+            button.add_css_class("video-ptz__preset");
+            button.connect_clicked(move |_| {
+                messaging::send_message(VideoMessage::PtzPreset(token.clone()));
+            });
+
+            self.presets.append(&button);
+        }
+
+        self.wrapper.set_visible(true);
+    }
+}
+
+/// Moves only while the button is held
+fn hold_gesture(direction: Direction) -> gtk::GestureClick {
+    let gesture = gtk::GestureClick::new();
+    gesture.set_propagation_phase(gtk::PropagationPhase::Capture);
+
+    gesture.connect_pressed(move |_, _, _, _| {
+        messaging::send_message(VideoMessage::PtzMove(Some(direction)));
+    });
+    gesture.connect_released(|_, _, _, _| messaging::send_message(VideoMessage::PtzMove(None)));
+    gesture.connect_cancel(|_, _| messaging::send_message(VideoMessage::PtzMove(None)));
+
+    gesture
+}
+
+fn spawn_worker() -> mpsc::Sender<Job> {
+    let (sender, receiver) = mpsc::channel::<Job>();
+
+    std::thread::spawn(move || {
+        for job in receiver {
+            job();
+        }
+    });
+
+    sender
+}
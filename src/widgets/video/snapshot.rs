@@ -0,0 +1,202 @@
+use std::path::{Path, PathBuf};
+
+use clapper::Player;
+use gtk::{gdk, gdk_pixbuf, gio, glib};
+
+use crate::config::{self, SnapshotFormat};
+use crate::prelude::*;
+
+const THUMBNAIL_WIDTH: i32 = 160;
+const THUMBNAIL_HEIGHT: i32 = 90;
+
+/// Saves frames of the active stream and shows the most recent ones
+pub struct Snapshots {
+    config: config::Snapshots,
+
+    wrapper: gtk::Box,
+    picture: gtk::Picture,
+    thumbnails: gtk::Box,
+}
+
+impl Snapshots {
+    pub fn new(config: &config::Snapshots) -> Self {
+        let picture = gtk::Picture::new();
+        picture.add_css_class("video-gallery__picture");
+        picture.set_expand(true);
+        picture.set_content_fit(gtk::ContentFit::Contain);
+
+        let thumbnails = gtk::Box::new(gtk::Orientation::Horizontal, 8);
+        thumbnails.add_css_class("video-gallery__thumbnails");
+
+        let scrolled = gtk::ScrolledWindow::new();
+        scrolled.set_hexpand(true);
+        scrolled.set_policy(gtk::PolicyType::Automatic, gtk::PolicyType::Never);
+        scrolled.set_child(Some(&thumbnails));
+
+        let wrapper = gtk::Box::new(gtk::Orientation::Vertical, 8);
+        wrapper.add_css_class("video-gallery");
+        wrapper.append(&picture);
+        wrapper.append(&scrolled);
+
+        Self {
+            config: config.clone(),
+            wrapper,
+            picture,
+            thumbnails,
+        }
+    }
+
+    pub const fn widget(&self) -> &gtk::Box {
+        &self.wrapper
+    }
+
+    /// Save the current frame of the player as `<directory>/<name>-<timestamp>.<format>`.
+    ///
+    /// # Errors
+    /// Returns an error if there is no frame yet or writing the file fails.
+    pub fn capture(
+        &self,
+        video: &clapper_gtk::Video,
+        player: &Player,
+        name: &str,
+    ) -> Result<PathBuf, String> {
+        let texture = frame(video, player).ok_or("no frame to capture")?;
+
+        std::fs::create_dir_all(&self.config.directory).map_err(|err| err.to_string())?;
+
+        let name: String = name
+            .chars()
+            .map(|c| if c.is_alphanumeric() { c } else { '_' })
+            .collect();
+        let path = self.config.directory.join(format!(
+            "{name}-{}.{}",
+            Local::now().format("%Y%m%d-%H%M%S-%3f"),
+            self.config.format.extension(),
+        ));
+
+        match self.config.format {
+            SnapshotFormat::Png => texture.save_to_png(&path).map_err(|err| err.to_string())?,
+            SnapshotFormat::Jpeg => {
+                let mut downloader = gdk::TextureDownloader::new(&texture);
+                downloader.set_format(gdk::MemoryFormat::R8g8b8);
+                let (bytes, stride) = downloader.download_bytes();
+
+                gdk_pixbuf::Pixbuf::from_bytes(
+                    &bytes,
+                    gdk_pixbuf::Colorspace::Rgb,
+                    false,
+                    8,
+                    texture.width(),
+                    texture.height(),
+                    stride as i32,
+                )
+                .savev(&path, "jpeg", &[("quality", "90")])
+                .map_err(|err| err.to_string())?;
+            }
+        }
+
+        Ok(path)
+    }
+
+    /// Reload the recent snapshots from the directory, newest first.
+    pub fn refresh(&self) {
+        while let Some(child) = self.thumbnails.first_child() {
+            self.thumbnails.remove(&child);
+        }
+
+        let paths = recent(&self.config.directory, self.config.gallery_size);
+
+        self.picture.set_filename(paths.first());
+
+        for path in paths {
+            let thumbnail = gtk::Picture::new();
+            thumbnail.set_content_fit(gtk::ContentFit::Cover);
+            thumbnail.set_size_request(THUMBNAIL_WIDTH, THUMBNAIL_HEIGHT);
+            load_thumbnail(&thumbnail, path.clone());
+
+            let picture = self.picture.clone();
+            let button = gtk::Button::new();
+            button.add_css_class("video-gallery__thumbnail");
+            button.set_child(Some(&thumbnail));
+            button.connect_clicked(move |_| picture.set_filename(Some(&path)));
+
+            self.thumbnails.append(&button);
+        }
+    }
+}
+
+/// Decode a scaled down copy of the snapshot off the main thread
+fn load_thumbnail(thumbnail: &gtk::Picture, path: PathBuf) {
+    glib::spawn_future_local(glib::clone!(
+        #[weak]
+        thumbnail,
+        async move {
+            // Twice the size, to stay sharp on high resolution screens
+            let pixbuf = gio::spawn_blocking(move || {
+                gdk_pixbuf::Pixbuf::from_file_at_scale(
+                    &path,
+                    THUMBNAIL_WIDTH * 2,
+                    THUMBNAIL_HEIGHT * 2,
+                    true,
+                )
+                .map_err(|err| format!("{}: {err}", path.display()))
+            })
+            .await;
+
+            match pixbuf {
+                Ok(Ok(pixbuf)) => thumbnail.set_paintable(Some(&gdk::Texture::for_pixbuf(&pixbuf))),
+                Ok(Err(err)) => log::warn!("Video player: failed to load snapshot {err}"),
+                Err(_) => log::warn!("Video player: loading a snapshot panicked"),
+            }
+        }
+    ));
+}
+
+/// Render the latest frame, preferring the paintable of the pipeline's video sink
+fn frame(video: &clapper_gtk::Video, player: &Player) -> Option<gdk::Texture> {
+    let paintable = player
+        .property::<Option<glib::Object>>("video-sink")
+        .filter(|sink| sink.find_property("paintable").is_some())
+        .map_or_else(
+            || gtk::WidgetPaintable::new(Some(video)).upcast(),
+            |sink| sink.property::<gdk::Paintable>("paintable"),
+        );
+
+    let (width, height) = match (paintable.intrinsic_width(), paintable.intrinsic_height()) {
+        (width, height) if width > 0 && height > 0 => (width, height),
+        _ => (video.width(), video.height()),
+    };
+
+    let snapshot = gtk::Snapshot::new();
+    paintable.snapshot(&snapshot, f64::from(width), f64::from(height));
+
+    let node = snapshot.to_node()?;
+    let renderer = video.native()?.renderer()?;
+
+    Some(renderer.render_texture(node, None))
+}
+
+fn recent(directory: &Path, limit: usize) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(directory) else {
+        return Vec::new();
+    };
+
+    let mut files: Vec<_> = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| matches!(ext, "png" | "jpg" | "jpeg"))
+        })
+        .filter_map(|path| Some((path.metadata().ok()?.modified().ok()?, path)))
+        .collect();
+
+    files.sort_by(|a, b| b.0.cmp(&a.0));
+
+    files
+        .into_iter()
+        .take(limit)
+        .map(|(_, path)| path)
+        .collect()
+}
@@ -0,0 +1,362 @@
+use clapper::MediaItem;
+use gst::glib;
+use gst::prelude::*;
+use url::Url;
+
+use crate::config::{Transport, Video};
+
+/// URI scheme of [`PipelineSource`], the pipeline description is passed in the query
+const PROTOCOL: &str = "hcp-pipeline";
+
+/// Media item of a stream, going through a custom source pipeline if it has low-latency or
+/// decoding options
+pub fn media_item(video: &Video) -> MediaItem {
+    MediaItem::builder()
+        .uri(&uri(video))
+        .name(&video.name)
+        .build()
+}
+
+fn uri(video: &Video) -> String {
+    let Some(description) = video
+        .pipeline
+        .clone()
+        .or_else(|| rtsp_description(video))
+        .or_else(|| {
+            video
+                .hardware_decoding
+                .map(|_| format!("urisourcebin uri=\"{}\"", video.url))
+        })
+    else {
+        return video.url.to_string();
+    };
+
+    let mut params = vec![("description", description)];
+    if let Some(hardware_decoding) = video.hardware_decoding {
+        params.push(("hardware", hardware_decoding.to_string()));
+    }
+
+    Url::parse_with_params(&format!("{PROTOCOL}:source"), &params)
+        .map_or_else(|_| video.url.to_string(), String::from)
+}
+
+/// `rtspsrc` with the configured latency and transport, which playbin does not allow to set
+fn rtsp_description(video: &Video) -> Option<String> {
+    if video.latency.is_none() && video.transport.is_none() {
+        return None;
+    }
+
+    if !matches!(video.url.scheme(), "rtsp" | "rtsps") {
+        log::warn!(
+            "Video player: latency and transport only apply to RTSP streams, ignoring them for \"{}\"",
+            video.name
+        );
+        return None;
+    }
+
+    let mut description = format!("rtspsrc location=\"{}\"", video.url);
+
+    if let Some(latency) = video.latency {
+        description.push_str(&format!(" latency={latency}"));
+    }
+
+    match video.transport {
+        Some(Transport::Tcp) => description.push_str(" protocols=tcp"),
+        Some(Transport::Udp) => description.push_str(" protocols=udp"),
+        None => (),
+    }
+
+    Some(description)
+}
+
+fn param(uri: &str, name: &str) -> Option<String> {
+    Url::parse(uri)
+        .ok()?
+        .query_pairs()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.into_owned())
+}
+
+fn description(uri: &str) -> Option<String> {
+    param(uri, "description")
+}
+
+/// Whether the source decodes its streams itself, only with hardware decoders or without them
+fn hardware_decoding(uri: &str) -> Option<bool> {
+    param(uri, "hardware")?.parse().ok()
+}
+
+/// Makes [`PipelineSource`] available to playbin
+pub fn register() {
+    if let Err(err) = gst::Element::register(
+        None,
+        "hcppipelinesrc",
+        gst::Rank::PRIMARY,
+        PipelineSource::static_type(),
+    ) {
+        log::error!("Video player: failed to register pipeline source: {err}");
+    }
+}
+
+glib::wrapper! {
+    /// Source bin built from the pipeline description in its URI. Unlinked source pads,
+    /// including ones added later like those of `rtspsrc`, are exposed for playbin to decode,
+    /// or decoded here if the URI restricts hardware decoding.
+    pub struct PipelineSource(ObjectSubclass<imp::PipelineSource>)
+        @extends gst::Bin, gst::Element, gst::Object,
+        @implements gst::URIHandler, gst::ChildProxy;
+}
+
+mod imp {
+    use std::sync::{LazyLock, Mutex};
+
+    use gst::glib;
+    use gst::prelude::*;
+    use gst::subclass::prelude::*;
+
+    #[derive(Default)]
+    pub struct PipelineSource {
+        uri: Mutex<Option<String>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for PipelineSource {
+        const NAME: &'static str = "HcpPipelineSource";
+        type Type = super::PipelineSource;
+        type ParentType = gst::Bin;
+        type Interfaces = (gst::URIHandler,);
+    }
+
+    impl ObjectImpl for PipelineSource {}
+
+    impl GstObjectImpl for PipelineSource {}
+
+    impl ElementImpl for PipelineSource {
+        fn metadata() -> Option<&'static gst::subclass::ElementMetadata> {
+            static METADATA: LazyLock<gst::subclass::ElementMetadata> = LazyLock::new(|| {
+                gst::subclass::ElementMetadata::new(
+                    "Pipeline source",
+                    "Source/Network",
+                    "Source built from the pipeline description in its URI",
+                    env!("CARGO_PKG_AUTHORS"),
+                )
+            });
+
+            Some(&METADATA)
+        }
+
+        fn pad_templates() -> &'static [gst::PadTemplate] {
+            static TEMPLATES: LazyLock<Vec<gst::PadTemplate>> = LazyLock::new(|| {
+                vec![
+                    gst::PadTemplate::new(
+                        "src_%u",
+                        gst::PadDirection::Src,
+                        gst::PadPresence::Sometimes,
+                        &gst::Caps::new_any(),
+                    )
+                    .unwrap(),
+                ]
+            });
+
+            &TEMPLATES
+        }
+
+        fn change_state(
+            &self,
+            transition: gst::StateChange,
+        ) -> Result<gst::StateChangeSuccess, gst::StateChangeError> {
+            if transition == gst::StateChange::NullToReady && self.obj().children().is_empty() {
+                self.build()?;
+            }
+
+            self.parent_change_state(transition)
+        }
+    }
+
+    impl BinImpl for PipelineSource {}
+
+    impl URIHandlerImpl for PipelineSource {
+        const URI_TYPE: gst::URIType = gst::URIType::Src;
+
+        fn protocols() -> &'static [&'static str] {
+            &[super::PROTOCOL]
+        }
+
+        fn uri(&self) -> Option<String> {
+            self.uri.lock().unwrap().clone()
+        }
+
+        fn set_uri(&self, uri: &str) -> Result<(), glib::Error> {
+            if super::description(uri).is_none() {
+                return Err(glib::Error::new(
+                    gst::URIError::BadUri,
+                    "Missing pipeline description",
+                ));
+            }
+
+            *self.uri.lock().unwrap() = Some(uri.to_string());
+
+            Ok(())
+        }
+    }
+
+    impl PipelineSource {
+        fn build(&self) -> Result<(), gst::StateChangeError> {
+            let uri = self.uri.lock().unwrap().clone();
+            let hardware_decoding = uri.as_deref().and_then(super::hardware_decoding);
+            let Some(description) = uri.as_deref().and_then(super::description) else {
+                gst::element_imp_error!(
+                    self,
+                    gst::ResourceError::NotFound,
+                    ["No pipeline description"]
+                );
+                return Err(gst::StateChangeError);
+            };
+
+            let inner = gst::parse::bin_from_description(&description, false).map_err(|err| {
+                gst::element_imp_error!(
+                    self,
+                    gst::ResourceError::Settings,
+                    ["Invalid pipeline \"{}\": {}", description, err]
+                );
+                gst::StateChangeError
+            })?;
+
+            let obj = self.obj().clone();
+            obj.add(&inner).map_err(|_| gst::StateChangeError)?;
+
+            for element in inner.iterate_recurse().into_iter().flatten() {
+                for pad in element.src_pads() {
+                    expose(&obj, &inner, &pad, hardware_decoding);
+                }
+
+                // Runs after the delayed linking of the description, so only unlinked pads remain
+                element.connect_pad_added(glib::clone!(
+                    #[weak]
+                    obj,
+                    #[weak]
+                    inner,
+                    move |_, pad| expose(&obj, &inner, pad, hardware_decoding)
+                ));
+
+                element.connect_no_more_pads(glib::clone!(
+                    #[weak]
+                    obj,
+                    move |_| obj.no_more_pads()
+                ));
+            }
+
+            Ok(())
+        }
+    }
+
+    /// Ghosts a pad through the inner bin and this source, decoding it first if hardware
+    /// decoding is restricted
+    fn expose(
+        obj: &super::PipelineSource,
+        inner: &gst::Bin,
+        pad: &gst::Pad,
+        hardware_decoding: Option<bool>,
+    ) {
+        if pad.direction() != gst::PadDirection::Src || pad.is_linked() {
+            return;
+        }
+
+        let exposed = gst::GhostPad::with_target(pad).and_then(|inner_pad| {
+            inner_pad.set_active(true)?;
+            inner.add_pad(&inner_pad)?;
+
+            match hardware_decoding {
+                Some(hardware) => decode(obj, &inner_pad, hardware),
+                None => ghost(obj, &inner_pad),
+            }
+        });
+
+        if let Err(err) = exposed {
+            log::error!(
+                "Video player: failed to expose pipeline pad {}: {err}",
+                pad.name()
+            );
+        }
+    }
+
+    fn ghost(obj: &super::PipelineSource, pad: &gst::Pad) -> Result<(), glib::BoolError> {
+        let ghost = gst::GhostPad::with_target(pad)?;
+        ghost.set_active(true)?;
+        obj.add_pad(&ghost)
+    }
+
+    /// Links the pad to a `decodebin` limited to hardware or software decoders, playbin then
+    /// passes the raw streams through. Unlike the decoder ranks, which are global to the process,
+    /// this only affects the stream of this source.
+    fn decode(
+        obj: &super::PipelineSource,
+        pad: &gst::Pad,
+        hardware: bool,
+    ) -> Result<(), glib::BoolError> {
+        let decodebin = gst::ElementFactory::make("decodebin").build()?;
+
+        // The enum is registered by the playback plugin, so only once decodebin exists
+        let select_result = glib::Type::from_name("GstAutoplugSelectResult")
+            .ok_or_else(|| glib::bool_error!("Missing GstAutoplugSelectResult"))?;
+
+        decodebin.connect("autoplug-select", false, move |args| {
+            let caps = args[2].get::<gst::Caps>().ok()?;
+            let factory = args[3].get::<gst::ElementFactory>().ok()?;
+
+            glib::EnumClass::with_type(select_result)?.to_value(select(&factory, &caps, hardware))
+        });
+
+        decodebin.connect_pad_added(glib::clone!(
+            #[weak]
+            obj,
+            move |_, pad| {
+                if let Err(err) = ghost(&obj, pad) {
+                    log::error!(
+                        "Video player: failed to expose decoded pad {}: {err}",
+                        pad.name()
+                    );
+                }
+            }
+        ));
+
+        obj.add(&decodebin)?;
+        decodebin.sync_state_with_parent()?;
+
+        let sink = decodebin
+            .static_pad("sink")
+            .ok_or_else(|| glib::bool_error!("decodebin has no sink pad"))?;
+        pad.link(&sink)
+            .map_err(|err| glib::bool_error!("Failed to link decodebin: {err:?}"))?;
+
+        Ok(())
+    }
+
+    /// `GstAutoplugSelectResult` of a factory offered by decodebin: try or skip it
+    fn select(factory: &gst::ElementFactory, caps: &gst::Caps, hardware: bool) -> i32 {
+        const TRY: i32 = 0;
+        const SKIP: i32 = 2;
+
+        if !factory.has_type(gst::ElementFactoryType::DECODER) || is_hardware(factory) == hardware {
+            return TRY;
+        }
+
+        if !hardware {
+            return SKIP;
+        }
+
+        // Without a hardware decoder for the codec the stream still plays in software
+        let hardware_available = gst::ElementFactory::factories_with_type(
+            gst::ElementFactoryType::DECODER,
+            gst::Rank::MARGINAL,
+        )
+        .iter()
+        .any(|decoder| is_hardware(decoder) && decoder.can_sink_any_caps(caps));
+
+        if hardware_available { SKIP } else { TRY }
+    }
+
+    fn is_hardware(factory: &gst::ElementFactory) -> bool {
+        factory.klass().contains("Hardware")
+    }
+}
@@ -0,0 +1,71 @@
+use std::process::{Child, Command, Stdio};
+use std::time::Duration;
+
+use gtk::glib;
+
+use crate::messaging;
+use crate::messaging::VideoMessage;
+use crate::remove_source;
+
+/// Stops talking even if the release of the button got lost
+const MAX_DURATION: Duration = Duration::from_secs(60);
+
+/// Push-to-talk command, running only while the talk button is held. The audio is sent by the
+/// command, opening the ONVIF backchannel of the stream here is left for later.
+#[derive(Debug, Default)]
+pub struct Talk {
+    child: Option<Child>,
+    timeout: Option<glib::SourceId>,
+}
+
+impl Talk {
+    pub fn start(&mut self, command: &[String]) {
+        self.stop();
+
+        let Some((program, args)) = command.split_first() else {
+            return;
+        };
+
+        match Command::new(program)
+            .args(args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .spawn()
+        {
+            Ok(child) => {
+                self.child = Some(child);
+                self.timeout = Some(glib::timeout_add_local_once(MAX_DURATION, || {
+                    log::warn!("Video player: talking for too long, stopping");
+
+                    messaging::send_message(VideoMessage::Talk(false));
+                }));
+            }
+            Err(err) => {
+                log::error!("Video player: failed to start talk command \"{program}\": {err}")
+            }
+        }
+    }
+
+    /// Returns whether a talk command was running
+    pub fn stop(&mut self) -> bool {
+        remove_source(self.timeout.take());
+
+        let Some(mut child) = self.child.take() else {
+            return false;
+        };
+
+        if let Err(err) = child.kill() {
+            log::error!("Video player: failed to stop talk command: {err}");
+        }
+
+        let _ = child.wait();
+
+        true
+    }
+}
+
+impl Drop for Talk {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
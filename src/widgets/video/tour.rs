@@ -0,0 +1,175 @@
+use std::time::{Duration, Instant};
+
+use gtk::glib;
+
+use crate::config::Config;
+use crate::messaging;
+use crate::messaging::VideoMessage;
+use crate::prelude::*;
+
+/// Rotates through the streams, pausing after a stream was selected by hand
+pub struct Tour {
+    enabled: bool,
+    /// Seconds each stream is shown, streams with 0 are skipped
+    dwell: Vec<u32>,
+    pause: u32,
+    hold_until: Option<Instant>,
+    timeout: Option<glib::SourceId>,
+}
+
+impl Tour {
+    pub fn new(config: &Config) -> Option<Self> {
+        let tour = config.video_tour.as_ref()?;
+
+        Some(Self {
+            enabled: tour.enabled,
+            dwell: config
+                .videos
+                .iter()
+                .map(|video| video.dwell.unwrap_or(tour.dwell))
+                .collect(),
+            pause: tour.pause,
+            hold_until: None,
+            timeout: None,
+        })
+    }
+
+    pub const fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+
+        if enabled {
+            self.schedule(0);
+        } else {
+            self.stop();
+        }
+    }
+
+    /// The stream after `current` which takes part in the tour
+    pub fn next(&self, current: usize) -> Option<usize> {
+        let len = self.dwell.len();
+        let start = if current < len { current + 1 } else { 0 };
+
+        (0..len)
+            .map(|offset| (start + offset) % len)
+            .find(|idx| self.dwell[*idx] > 0)
+    }
+
+    /// Moves on after the dwell time of the shown stream
+    pub fn shown(&mut self, idx: usize) {
+        let dwell = self.dwell.get(idx).copied().unwrap_or_default();
+
+        // Streams outside of the tour are left after the pause instead
+        self.schedule(if dwell > 0 { dwell } else { self.pause });
+    }
+
+    /// Waits longer before moving on, e.g. after a manual selection
+    pub fn pause(&mut self) {
+        let seconds = self.pause_seconds(Instant::now());
+        self.schedule(seconds);
+    }
+
+    /// Keeps the next pause from ending before `duration` passed, e.g. for the doorbell
+    pub fn hold(&mut self, duration: Duration) {
+        self.hold_until = Some(Instant::now() + duration);
+    }
+
+    /// Stops rotating until the next stream is shown, e.g. for the grid or the screensaver
+    pub fn stop(&mut self) {
+        remove_source(self.timeout.take());
+    }
+
+    /// The pause, or the rest of a hold if it ends later. Partial seconds are rounded up,
+    /// so the tour never moves on before the hold ended.
+    fn pause_seconds(&mut self, now: Instant) -> u32 {
+        let hold = self.hold_until.take().map_or(0, |until| {
+            let millis = until.saturating_duration_since(now).as_millis();
+            u32::try_from(millis.div_ceil(1000)).unwrap_or(u32::MAX)
+        });
+
+        self.pause.max(hold)
+    }
+
+    fn schedule(&mut self, seconds: u32) {
+        remove_source(self.timeout.take());
+
+        if self.enabled {
+            self.timeout = Some(glib::timeout_add_seconds_local_once(seconds, || {
+                messaging::send_message(VideoMessage::TourNext);
+            }));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tour(dwell: &[u32]) -> Tour {
+        Tour {
+            enabled: true,
+            dwell: dwell.to_vec(),
+            pause: 60,
+            hold_until: None,
+            timeout: None,
+        }
+    }
+
+    #[test]
+    fn next_skips_streams_outside_of_the_tour() {
+        let tour = tour(&[10, 0, 10, 0]);
+
+        assert_eq!(tour.next(0), Some(2));
+        assert_eq!(tour.next(1), Some(2));
+        assert_eq!(tour.next(2), Some(0));
+        assert_eq!(tour.next(3), Some(0));
+    }
+
+    #[test]
+    fn next_starts_over_for_unknown_streams() {
+        let tour = tour(&[0, 10]);
+
+        assert_eq!(tour.next(usize::MAX), Some(1));
+        assert_eq!(tour.next(1), Some(1));
+    }
+
+    #[test]
+    fn next_without_streams() {
+        assert_eq!(tour(&[0, 0]).next(0), None);
+        assert_eq!(tour(&[]).next(0), None);
+    }
+
+    #[test]
+    fn pause_without_hold() {
+        let mut tour = tour(&[10]);
+
+        assert_eq!(tour.pause_seconds(Instant::now()), 60);
+    }
+
+    #[test]
+    fn pause_until_hold_ends() {
+        let now = Instant::now();
+        let mut tour = tour(&[10]);
+
+        tour.hold_until = Some(now + Duration::from_millis(90_500));
+        assert_eq!(tour.pause_seconds(now), 91);
+
+        // The hold only extends the next pause
+        assert_eq!(tour.pause_seconds(now), 60);
+    }
+
+    #[test]
+    fn pause_outlasts_short_hold() {
+        let now = Instant::now();
+        let mut tour = tour(&[10]);
+
+        tour.hold_until = Some(now + Duration::from_secs(30));
+        assert_eq!(tour.pause_seconds(now), 60);
+
+        tour.hold_until = Some(now);
+        assert_eq!(tour.pause_seconds(now + Duration::from_secs(5)), 60);
+    }
+}